@@ -0,0 +1,1442 @@
+//! Parsing of assembly text into [`Inst`], the inverse of the [`Display`] impl of [`Inst`].
+
+use core::fmt::{self, Display};
+use core::ops::{Range, RangeInclusive};
+use core::str::FromStr;
+
+use crate::vector::{Form, Kind};
+use crate::{
+    AmoOp, AmoOrdering, Csr, FReg, Fence, FenceSet, FliConstant, Imm, Inst, Lmul, OpfFunct6,
+    OpiFunct6, OpmFunct6, Reg, RoundingMode, Sew, VReg, VfUnaryOp, VfcvtOp, Vtype, Xlen,
+};
+
+/// The error used for assembly text that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseError {
+    /// The byte range of the input that caused the error.
+    pub span: Range<usize>,
+    /// A description of what was wrong with the input.
+    pub message: &'static str,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to parse instruction at {}..{}: {}",
+            self.span.start, self.span.end, self.message
+        )
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// Parses an instruction for RV64, see [`Inst::parse_asm`].
+///
+/// As RV64 is a superset of RV32, this accepts all instructions,
+/// including RV64-only instructions and shift amounts.
+impl FromStr for Inst {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Inst::parse_asm(s, Xlen::Rv64)
+    }
+}
+
+/// A cursor over the operands of a single line of assembly.
+struct Parser<'a> {
+    src: &'a str,
+    /// Byte offset of the remaining, unparsed operands.
+    pos: usize,
+    /// The number of operands that have been parsed.
+    operands: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(span: Range<usize>, message: &'static str) -> ParseError {
+        ParseError { span, message }
+    }
+
+    fn skip_whitespace(&self, from: usize) -> usize {
+        let rest = &self.src[from..];
+        from + (rest.len() - rest.trim_start().len())
+    }
+
+    /// Whether there are operands left.
+    fn has_operand(&self) -> bool {
+        !self.src[self.pos..].trim().is_empty()
+    }
+
+    /// The next comma-separated operand, trimmed, with its span.
+    fn operand(&mut self) -> Result<(&'a str, Range<usize>), ParseError> {
+        let mut start = self.skip_whitespace(self.pos);
+        if self.operands > 0 {
+            if !self.src[start..].starts_with(',') {
+                return Err(Self::error(start..start, "expected `,`"));
+            }
+            start = self.skip_whitespace(start + 1);
+        }
+        let rest = &self.src[start..];
+        let len = rest.find(',').unwrap_or(rest.len());
+        let text = rest[..len].trim_end();
+        self.pos = start + len;
+        self.operands += 1;
+        if text.is_empty() {
+            return Err(Self::error(start..start, "expected operand"));
+        }
+        Ok((text, start..start + text.len()))
+    }
+
+    /// Ensures that all operands have been consumed.
+    fn finish(&self) -> Result<(), ParseError> {
+        let start = self.skip_whitespace(self.pos);
+        if start < self.src.len() {
+            let end = self.src.trim_end().len();
+            return Err(Self::error(start..end, "unexpected operand"));
+        }
+        Ok(())
+    }
+
+    fn reg(&mut self) -> Result<Reg, ParseError> {
+        let (text, span) = self.operand()?;
+        parse_reg(text).ok_or_else(|| Self::error(span, "expected integer register"))
+    }
+
+    /// The even register of a register pair, like the operands of `amocas.q`.
+    fn reg_pair(&mut self) -> Result<Reg, ParseError> {
+        let (text, span) = self.operand()?;
+        match parse_reg(text) {
+            Some(reg) if reg.0 % 2 == 0 => Ok(reg),
+            Some(_) => Err(Self::error(span, "expected even register of a register pair")),
+            None => Err(Self::error(span, "expected integer register")),
+        }
+    }
+
+    fn freg(&mut self) -> Result<FReg, ParseError> {
+        let (text, span) = self.operand()?;
+        parse_freg(text).ok_or_else(|| Self::error(span, "expected floating-point register"))
+    }
+
+    fn vreg(&mut self) -> Result<VReg, ParseError> {
+        let (text, span) = self.operand()?;
+        parse_vreg(text).ok_or_else(|| Self::error(span, "expected vector register"))
+    }
+
+    /// An optional trailing `v0.t` of masked vector instructions.
+    fn vmask(&mut self) -> Result<bool, ParseError> {
+        if !self.has_operand() {
+            return Ok(false);
+        }
+        let (text, span) = self.operand()?;
+        if text != "v0.t" {
+            return Err(Self::error(span, "expected `v0.t`"));
+        }
+        Ok(true)
+    }
+
+    /// The `v0` operand of vector instructions that use it as carry input or merge mask.
+    fn v0(&mut self) -> Result<(), ParseError> {
+        let (text, span) = self.operand()?;
+        if text != "v0" {
+            return Err(Self::error(span, "expected `v0`"));
+        }
+        Ok(())
+    }
+
+    /// The vtype operands of `vsetvli` and `vsetivli`, like `e32, m1, ta, ma`, or their
+    /// `bits`-bit immediate. The tail and mask policies are optional and default to undisturbed.
+    fn vtype(&mut self, bits: u32) -> Result<Vtype, ParseError> {
+        let (text, span) = self.operand()?;
+        if parse_int(text).is_some() {
+            let bits = check_imm(text, span, 0..=(1 << bits) - 1, 1)?;
+            return Ok(Vtype(bits as u16));
+        }
+        let sew = text
+            .strip_prefix('e')
+            .and_then(parse_eew)
+            .ok_or_else(|| Self::error(span, "expected element width"))?;
+        let (text, span) = self.operand()?;
+        let lmul = match text {
+            "mf8" => Lmul::Mf8,
+            "mf4" => Lmul::Mf4,
+            "mf2" => Lmul::Mf2,
+            "m1" => Lmul::M1,
+            "m2" => Lmul::M2,
+            "m4" => Lmul::M4,
+            "m8" => Lmul::M8,
+            _ => return Err(Self::error(span, "expected register group multiplier")),
+        };
+        let (mut tail_agnostic, mut mask_agnostic) = (false, false);
+        if self.has_operand() {
+            let (text, span) = self.operand()?;
+            tail_agnostic = match text {
+                "ta" => true,
+                "tu" => false,
+                _ => return Err(Self::error(span, "expected `ta` or `tu`")),
+            };
+        }
+        if self.has_operand() {
+            let (text, span) = self.operand()?;
+            mask_agnostic = match text {
+                "ma" => true,
+                "mu" => false,
+                _ => return Err(Self::error(span, "expected `ma` or `mu`")),
+            };
+        }
+        Ok(Vtype::new(sew, lmul, tail_agnostic, mask_agnostic))
+    }
+
+    /// The operands `dest, src2, src1` of a vector arithmetic instruction
+    /// (`dest, src1, src2` for multiply-add), followed by the mask operand.
+    /// `masked` is whether the mnemonic has the suffix of the masked form (`.vvm`).
+    fn varith<T>(
+        &mut self,
+        kind: Kind,
+        masked: bool,
+        src1: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<(VReg, VReg, T, bool), ParseError> {
+        let dest = self.vreg()?;
+        let (src2, src1) = if kind == Kind::MulAdd {
+            let src1 = src1(self)?;
+            (self.vreg()?, src1)
+        } else {
+            (self.vreg()?, src1(self)?)
+        };
+        let masked = match kind {
+            Kind::Carry | Kind::CarryOut | Kind::Merge => {
+                if masked {
+                    self.v0()?;
+                }
+                masked
+            }
+            Kind::Mask | Kind::Compress => false,
+            _ => self.vmask()?,
+        };
+        Ok((dest, src2, src1, masked))
+    }
+
+    /// Parses vector loads and stores like `vlseg2e16ff.v` whose mnemonics contain
+    /// the number of fields and element width.
+    fn vector_mem(&mut self, mnemonic: &str) -> Result<Option<Inst>, ParseError> {
+        enum Mode {
+            Unit,
+            Strided,
+            Unordered,
+            Ordered,
+        }
+        let Some(body) = mnemonic.strip_suffix(".v") else {
+            return Ok(None);
+        };
+        let (store, body) = match (body.strip_prefix("vl"), body.strip_prefix("vs")) {
+            (Some(body), _) => (false, body),
+            (_, Some(body)) => (true, body),
+            _ => return Ok(None),
+        };
+        // whole register `vl<nf>re<eew>.v` and `vs<nf>r.v`
+        if let Some(rest) = body.strip_prefix(['1', '2', '4', '8']) {
+            let nf = body.as_bytes()[0] - b'0';
+            let eew = rest.strip_prefix("re").and_then(parse_eew);
+            if (store && rest != "r") || (!store && eew.is_none()) {
+                return Ok(None);
+            }
+            let (reg, base) = (self.vreg()?, self.amo_addr()?);
+            return Ok(Some(match eew {
+                Some(eew) => Inst::Vlr { eew, nf, dest: reg, base },
+                None => Inst::Vsr { nf, src: reg, base },
+            }));
+        }
+        let (mode, body) = if let Some(rest) = body.strip_prefix("ux") {
+            (Mode::Unordered, rest)
+        } else if let Some(rest) = body.strip_prefix("ox") {
+            (Mode::Ordered, rest)
+        } else if let Some(rest) = body.strip_prefix('s').filter(|_| !body.starts_with("seg")) {
+            (Mode::Strided, rest)
+        } else {
+            (Mode::Unit, body)
+        };
+        let (nf, body) = match body.strip_prefix("seg") {
+            Some(rest) => match rest.as_bytes().first() {
+                Some(&n @ b'2'..=b'8') => (n - b'0', &rest[1..]),
+                _ => return Ok(None),
+            },
+            None => (1, body),
+        };
+        let indexed = matches!(mode, Mode::Unordered | Mode::Ordered);
+        let Some(body) = body.strip_prefix(if indexed { "ei" } else { "e" }) else {
+            return Ok(None);
+        };
+        let (ff, body) = match body.strip_suffix("ff") {
+            Some(body) if matches!(mode, Mode::Unit) && !store => (true, body),
+            _ => (false, body),
+        };
+        let Some(eew) = parse_eew(body) else {
+            return Ok(None);
+        };
+        let (reg, base) = (self.vreg()?, self.amo_addr()?);
+        Ok(Some(match mode {
+            Mode::Unit => {
+                let masked = self.vmask()?;
+                match (store, ff) {
+                    (false, false) => Inst::Vle { eew, nf, dest: reg, base, masked },
+                    (false, true) => Inst::Vleff { eew, nf, dest: reg, base, masked },
+                    (true, _) => Inst::Vse { eew, nf, src: reg, base, masked },
+                }
+            }
+            Mode::Strided => {
+                let (stride, masked) = (self.reg()?, self.vmask()?);
+                if store {
+                    Inst::Vsse { eew, nf, src: reg, base, stride, masked }
+                } else {
+                    Inst::Vlse { eew, nf, dest: reg, base, stride, masked }
+                }
+            }
+            Mode::Unordered | Mode::Ordered => {
+                let (index, masked) = (self.vreg()?, self.vmask()?);
+                match (store, mode) {
+                    (false, Mode::Unordered) => Inst::Vluxei { eew, nf, dest: reg, base, index, masked },
+                    (false, _) => Inst::Vloxei { eew, nf, dest: reg, base, index, masked },
+                    (true, Mode::Unordered) => Inst::Vsuxei { eew, nf, src: reg, base, index, masked },
+                    (true, _) => Inst::Vsoxei { eew, nf, src: reg, base, index, masked },
+                }
+            }
+        }))
+    }
+
+    /// Parses the vector instructions whose mnemonics are looked up in tables:
+    /// loads and stores, arithmetic instructions and floating-point unary instructions.
+    fn vector(&mut self, mnemonic: &str) -> Result<Option<Inst>, ParseError> {
+        if let Some(inst) = self.vector_mem(mnemonic)? {
+            return Ok(Some(inst));
+        }
+        if let Some(op) = VfcvtOp::parse(mnemonic) {
+            let (dest, src, masked) = (self.vreg()?, self.vreg()?, self.vmask()?);
+            return Ok(Some(Inst::Vfcvt { op, dest, src, masked }));
+        }
+        if let Some(op) = VfUnaryOp::parse(mnemonic) {
+            let (dest, src, masked) = (self.vreg()?, self.vreg()?, self.vmask()?);
+            return Ok(Some(Inst::VfUnary { op, dest, src, masked }));
+        }
+        if let Some((op, form, masked)) = OpiFunct6::parse(mnemonic) {
+            let kind = op.kind();
+            return Ok(Some(match form {
+                Form::V => {
+                    let (dest, src2, src1, masked) = self.varith(kind, masked, Self::vreg)?;
+                    Inst::Opivv { op, dest, src2, src1, masked }
+                }
+                Form::X => {
+                    let (dest, src2, src1, masked) = self.varith(kind, masked, Self::reg)?;
+                    Inst::Opivx { op, dest, src2, src1, masked }
+                }
+                _ => {
+                    let range = if op.has_unsigned_imm() { 0..=31 } else { -16..=15 };
+                    let (dest, src2, imm, masked) =
+                        self.varith(kind, masked, |p| p.imm(range, 1))?;
+                    let imm = Imm::new_i32(imm as i32);
+                    Inst::Opivi { op, dest, src2, imm, masked }
+                }
+            }));
+        }
+        if let Some((op, form, masked)) = OpmFunct6::parse(mnemonic) {
+            let kind = op.kind();
+            return Ok(Some(match form {
+                Form::V => {
+                    let (dest, src2, src1, masked) = self.varith(kind, masked, Self::vreg)?;
+                    Inst::Opmvv { op, dest, src2, src1, masked }
+                }
+                _ => {
+                    let (dest, src2, src1, masked) = self.varith(kind, masked, Self::reg)?;
+                    Inst::Opmvx { op, dest, src2, src1, masked }
+                }
+            }));
+        }
+        if let Some((op, form, masked)) = OpfFunct6::parse(mnemonic) {
+            let kind = op.kind();
+            return Ok(Some(match form {
+                Form::V => {
+                    let (dest, src2, src1, masked) = self.varith(kind, masked, Self::vreg)?;
+                    Inst::Opfvv { op, dest, src2, src1, masked }
+                }
+                _ => {
+                    let (dest, src2, src1, masked) = self.varith(kind, masked, Self::freg)?;
+                    Inst::Opfvf { op, dest, src2, src1, masked }
+                }
+            }));
+        }
+        Ok(None)
+    }
+
+    /// An immediate that must be in `range` and a multiple of `align`.
+    fn imm(&mut self, range: RangeInclusive<i64>, align: i64) -> Result<i64, ParseError> {
+        let (text, span) = self.operand()?;
+        check_imm(text, span, range, align)
+    }
+
+    /// A signed 12-bit immediate of I-type instructions.
+    fn imm_i(&mut self) -> Result<Imm, ParseError> {
+        self.imm(-2048..=2047, 1).map(|v| Imm::new_i32(v as i32))
+    }
+
+    /// A shift amount for a register of `bits` bits.
+    fn shamt(&mut self, bits: i64) -> Result<Imm, ParseError> {
+        self.imm(0..=bits - 1, 1).map(|v| Imm::new_u32(v as u32))
+    }
+
+    /// A memory operand of the form `offset(base)`, where the offset is optional.
+    fn mem(&mut self, range: RangeInclusive<i64>, align: i64) -> Result<(Imm, Reg), ParseError> {
+        let (text, span) = self.operand()?;
+        let (Some(open), Some(inner)) = (text.find('('), text.strip_suffix(')')) else {
+            return Err(Self::error(span, "expected memory operand `offset(base)`"));
+        };
+        let offset_text = text[..open].trim_end();
+        let offset = if offset_text.is_empty() {
+            0
+        } else {
+            check_imm(
+                offset_text,
+                span.start..span.start + offset_text.len(),
+                range,
+                align,
+            )?
+        };
+        let base_text = &inner[open + 1..];
+        let base_start = span.start + open + 1 + (base_text.len() - base_text.trim_start().len());
+        let base_text = base_text.trim();
+        let base = parse_reg(base_text).ok_or_else(|| {
+            Self::error(
+                base_start..base_start + base_text.len(),
+                "expected integer register",
+            )
+        })?;
+        Ok((Imm::new_i32(offset as i32), base))
+    }
+
+    /// The address operand of atomic instructions, `(addr)` or `0(addr)`.
+    fn amo_addr(&mut self) -> Result<Reg, ParseError> {
+        self.mem(0..=0, 1).map(|(_, base)| base)
+    }
+
+    fn fence_set(&mut self) -> Result<FenceSet, ParseError> {
+        let (text, span) = self.operand()?;
+        let mut set = FenceSet {
+            device_input: false,
+            device_output: false,
+            memory_read: false,
+            memory_write: false,
+        };
+        if text == "0" {
+            return Ok(set);
+        }
+        for c in text.chars() {
+            let bit = match c {
+                'i' => &mut set.device_input,
+                'o' => &mut set.device_output,
+                'r' => &mut set.memory_read,
+                'w' => &mut set.memory_write,
+                _ => return Err(Self::error(span, "expected fence set of `iorw` or `0`")),
+            };
+            if *bit {
+                return Err(Self::error(span, "duplicate entry in fence set"));
+            }
+            *bit = true;
+        }
+        Ok(set)
+    }
+
+    /// A CSR name like `mstatus` or address like `0x300`.
+    fn csr(&mut self) -> Result<Csr, ParseError> {
+        let (text, span) = self.operand()?;
+        if let Some(csr) = Csr::from_name(text) {
+            return Ok(csr);
+        }
+        check_imm(text, span, 0..=0xfff, 1).map(|v| Csr(v as u16))
+    }
+
+    /// A destination register that may be left out when it's `zero`, as in `fscsr a0` and
+    /// `fscsr a0, a1`, given the number of operands that follow it.
+    fn optional_dest(&mut self, following: usize) -> Result<Reg, ParseError> {
+        let remaining = self.src[self.pos..].matches(',').count() + usize::from(self.operands == 0);
+        if remaining > following {
+            self.reg()
+        } else {
+            Ok(Reg::ZERO)
+        }
+    }
+
+    /// A constant of `fli`, like `min` or `0.25`.
+    fn fli(&mut self) -> Result<FliConstant, ParseError> {
+        let (text, span) = self.operand()?;
+        FliConstant::from_name(text).ok_or_else(|| Self::error(span, "expected fli constant"))
+    }
+
+    /// An optional trailing rounding mode, [`RoundingMode::Dynamic`] if absent.
+    fn rm(&mut self) -> Result<RoundingMode, ParseError> {
+        if !self.has_operand() {
+            return Ok(RoundingMode::Dynamic);
+        }
+        let (text, span) = self.operand()?;
+        Ok(match text {
+            "rne" => RoundingMode::RoundToNearestTiesToEven,
+            "rtz" => RoundingMode::RoundTowardsZero,
+            "rdn" => RoundingMode::RoundDown,
+            "rup" => RoundingMode::RoundUp,
+            "rmm" => RoundingMode::RoundToNearestTiesToMax,
+            "dyn" => RoundingMode::Dynamic,
+            _ => return Err(Self::error(span, "expected rounding mode")),
+        })
+    }
+
+    /// The optional rounding mode of exact conversions like `fcvt.s.h`, which defaults to `rne`
+    /// like in the GNU and LLVM assemblers.
+    fn exact_rm(&mut self) -> Result<RoundingMode, ParseError> {
+        if !self.has_operand() {
+            return Ok(RoundingMode::RoundToNearestTiesToEven);
+        }
+        self.rm()
+    }
+}
+
+fn check_imm(
+    text: &str,
+    span: Range<usize>,
+    range: RangeInclusive<i64>,
+    align: i64,
+) -> Result<i64, ParseError> {
+    let value = parse_int(text).ok_or_else(|| Parser::error(span.clone(), "expected immediate"))?;
+    if !range.contains(&value) {
+        return Err(Parser::error(span, "immediate out of range"));
+    }
+    if value % align != 0 {
+        return Err(Parser::error(span, "immediate is not aligned"));
+    }
+    Ok(value)
+}
+
+/// Parses a decimal or `0x`-prefixed hexadecimal integer with an optional sign.
+fn parse_int(text: &str) -> Option<i64> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (digits, radix) = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => (hex, 16),
+        None => (digits, 10),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    let value = i128::from(u64::from_str_radix(digits, radix).ok()?);
+    i64::try_from(if negative { -value } else { value }).ok()
+}
+
+/// Splits a register name like `a10` into `("a", 10)`.
+fn split_reg_name(name: &str) -> Option<(&str, u8)> {
+    let index = name.find(|c: char| c.is_ascii_digit())?;
+    let (prefix, number) = name.split_at(index);
+    if !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((prefix, number.parse().ok()?))
+}
+
+/// Parses ABI (`a0`) and numeric (`x10`) integer register names.
+pub(crate) fn parse_reg(name: &str) -> Option<Reg> {
+    let n = match name {
+        "zero" => 0,
+        "ra" => 1,
+        "sp" => 2,
+        "gp" => 3,
+        "tp" => 4,
+        "fp" => 8,
+        _ => match split_reg_name(name)? {
+            ("x", n @ 0..=31) => n,
+            ("t", n @ 0..=2) => n + 5,
+            ("t", n @ 3..=6) => n - 3 + 28,
+            ("s", n @ 0..=1) => n + 8,
+            ("s", n @ 2..=11) => n - 2 + 18,
+            ("a", n @ 0..=7) => n + 10,
+            _ => return None,
+        },
+    };
+    Some(Reg(n))
+}
+
+/// Parses ABI (`fa0`) and numeric (`f10`) floating-point register names.
+pub(crate) fn parse_freg(name: &str) -> Option<FReg> {
+    let n = match split_reg_name(name)? {
+        ("f", n @ 0..=31) => n,
+        ("ft", n @ 0..=7) => n,
+        ("ft", n @ 8..=11) => n - 8 + 28,
+        ("fs", n @ 0..=1) => n + 8,
+        ("fs", n @ 2..=11) => n - 2 + 18,
+        ("fa", n @ 0..=7) => n + 10,
+        _ => return None,
+    };
+    Some(FReg(n))
+}
+
+/// Parses vector register names (`v8`).
+fn parse_vreg(name: &str) -> Option<VReg> {
+    match split_reg_name(name)? {
+        ("v", n @ 0..=31) => Some(VReg(n)),
+        _ => None,
+    }
+}
+
+/// Parses the element width in vector mnemonics and `vtype`, like the `32` of `vle32.v`.
+fn parse_eew(bits: &str) -> Option<Sew> {
+    match bits {
+        "8" => Some(Sew::E8),
+        "16" => Some(Sew::E16),
+        "32" => Some(Sew::E32),
+        "64" => Some(Sew::E64),
+        _ => None,
+    }
+}
+
+/// Splits the `.aq`/`.rl`/`.aqrl` suffix off atomic instructions.
+fn split_amo_ordering(mnemonic: &str) -> (&str, AmoOrdering) {
+    if !(mnemonic.starts_with("amo") || mnemonic.starts_with("lr.") || mnemonic.starts_with("sc."))
+    {
+        return (mnemonic, AmoOrdering::Relaxed);
+    }
+    for (suffix, order) in [
+        (".aqrl", AmoOrdering::SeqCst),
+        (".aq", AmoOrdering::Acquire),
+        (".rl", AmoOrdering::Release),
+    ] {
+        if let Some(base) = mnemonic.strip_suffix(suffix) {
+            return (base, order);
+        }
+    }
+    (mnemonic, AmoOrdering::Relaxed)
+}
+
+impl Inst {
+    /// Parse a single instruction from assembly text.
+    ///
+    /// This accepts everything that is printed by the [`Display`] impl of [`Inst`],
+    /// including pseudo-instructions like `ret`, `j`, `pause` or `fence.tso` and
+    /// optional rounding modes of floating-point instructions.
+    /// Registers may be written with their ABI names (`a0`) or numerically (`x10`),
+    /// and immediates may be decimal or `0x`-prefixed hexadecimal.
+    ///
+    /// Instructions that are not available for the XLEN are rejected.
+    /// Errors contain the byte range of the offending part of the line.
+    ///
+    /// # Example
+    /// ```rust
+    /// let inst = rv_asm::Inst::parse_asm("addi sp, sp, -32", rv_asm::Xlen::Rv32).unwrap();
+    /// let expected = rv_asm::Inst::Addi { imm: rv_asm::Imm::new_i32(-0x20), dest: rv_asm::Reg::SP, src1: rv_asm::Reg::SP };
+    /// assert_eq!(inst, expected);
+    ///
+    /// let err = rv_asm::Inst::parse_asm("addi sp, sp, 4096", rv_asm::Xlen::Rv32).unwrap_err();
+    /// assert_eq!(err.span, 13..17);
+    /// ```
+    pub fn parse_asm(line: &str, xlen: Xlen) -> Result<Inst, ParseError> {
+        let start = line.len() - line.trim_start().len();
+        let rest = &line[start..];
+        let end = start + rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let mnemonic_span = start..end;
+        if mnemonic_span.is_empty() {
+            return Err(Parser::error(mnemonic_span, "expected instruction"));
+        }
+        let (mnemonic, order) = split_amo_ordering(&line[mnemonic_span.clone()]);
+        let mut p = Parser {
+            src: line,
+            pos: end,
+            operands: 0,
+        };
+
+        let xlen_bits = match xlen {
+            Xlen::Rv32 => 32,
+            Xlen::Rv64 => 64,
+        };
+
+        macro_rules! rv64 {
+            ($inst:expr) => {{
+                if xlen.is_32() {
+                    return Err(Parser::error(
+                        mnemonic_span,
+                        "instruction is only available on RV64",
+                    ));
+                }
+                $inst
+            }};
+        }
+        macro_rules! rv32 {
+            ($inst:expr) => {{
+                if !xlen.is_32() {
+                    return Err(Parser::error(
+                        mnemonic_span,
+                        "instruction is only available on RV32",
+                    ));
+                }
+                $inst
+            }};
+        }
+        macro_rules! r {
+            ($variant:ident) => {{
+                let (dest, src1, src2) = (p.reg()?, p.reg()?, p.reg()?);
+                Inst::$variant { dest, src1, src2 }
+            }};
+        }
+        macro_rules! unary {
+            ($variant:ident) => {{
+                let (dest, src) = (p.reg()?, p.reg()?);
+                Inst::$variant { dest, src }
+            }};
+        }
+        macro_rules! i {
+            ($variant:ident) => {{
+                let (dest, src1, imm) = (p.reg()?, p.reg()?, p.imm_i()?);
+                Inst::$variant { imm, dest, src1 }
+            }};
+        }
+        macro_rules! shift {
+            ($variant:ident, $bits:expr) => {{
+                let (dest, src1, imm) = (p.reg()?, p.reg()?, p.shamt($bits)?);
+                Inst::$variant { imm, dest, src1 }
+            }};
+        }
+        macro_rules! branch {
+            ($variant:ident) => {{
+                let (src1, src2) = (p.reg()?, p.reg()?);
+                let offset = Imm::new_i32(p.imm(-4096..=4094, 2)? as i32);
+                Inst::$variant { offset, src1, src2 }
+            }};
+        }
+        macro_rules! load {
+            ($variant:ident, $reg:ident) => {{
+                let dest = p.$reg()?;
+                let (offset, base) = p.mem(-2048..=2047, 1)?;
+                Inst::$variant { offset, dest, base }
+            }};
+        }
+        macro_rules! store {
+            ($variant:ident, $reg:ident) => {{
+                let src = p.$reg()?;
+                let (offset, base) = p.mem(-2048..=2047, 1)?;
+                Inst::$variant { offset, src, base }
+            }};
+        }
+        // Both operands are optional and default to zero, as in `sfence.vma` or `sfence.vma a0`.
+        macro_rules! fence_vma {
+            ($variant:ident, $rs1:ident, $rs2:ident) => {{
+                let $rs1 = if p.has_operand() { p.reg()? } else { Reg::ZERO };
+                let $rs2 = if p.has_operand() { p.reg()? } else { Reg::ZERO };
+                Inst::$variant { $rs1, $rs2 }
+            }};
+        }
+        macro_rules! amo {
+            ($variant:ident, $op:ident) => {{
+                let (dest, src, addr) = (p.reg()?, p.reg()?, p.amo_addr()?);
+                Inst::$variant {
+                    order,
+                    op: AmoOp::$op,
+                    dest,
+                    addr,
+                    src,
+                }
+            }};
+        }
+        // `pair` is whether `dest` and `src` are register pairs
+        macro_rules! amocas {
+            ($variant:ident, $pair:expr) => {{
+                let reg = |p: &mut Parser<'_>| if $pair { p.reg_pair() } else { p.reg() };
+                let (dest, src, addr) = (reg(&mut p)?, reg(&mut p)?, p.amo_addr()?);
+                Inst::$variant {
+                    order,
+                    dest,
+                    addr,
+                    src,
+                }
+            }};
+        }
+        macro_rules! csr {
+            ($variant:ident) => {{
+                let (dest, csr, src) = (p.reg()?, p.csr()?, p.reg()?);
+                Inst::$variant { csr, dest, src }
+            }};
+        }
+        macro_rules! csri {
+            ($variant:ident) => {{
+                let (dest, csr) = (p.reg()?, p.csr()?);
+                let uimm = Imm::new_u32(p.imm(0..=31, 1)? as u32);
+                Inst::$variant { csr, dest, uimm }
+            }};
+        }
+        // `dest, src1, src2, src3[, rm]` floating-point
+        macro_rules! fr4 {
+            ($variant:ident) => {{
+                let (dest, src1, src2, src3) = (p.freg()?, p.freg()?, p.freg()?, p.freg()?);
+                let rm = p.rm()?;
+                Inst::$variant { rm, dest, src1, src2, src3 }
+            }};
+        }
+        // `dest, src1, src2[, rm]` floating-point
+        macro_rules! fr3_rm {
+            ($variant:ident) => {{
+                let (dest, src1, src2) = (p.freg()?, p.freg()?, p.freg()?);
+                let rm = p.rm()?;
+                Inst::$variant { rm, dest, src1, src2 }
+            }};
+        }
+        // `dest, src1, src2` floating-point
+        macro_rules! fr3 {
+            ($variant:ident) => {{
+                let (dest, src1, src2) = (p.freg()?, p.freg()?, p.freg()?);
+                Inst::$variant { dest, src1, src2 }
+            }};
+        }
+        // `dest, src1, src2` with integer destination
+        macro_rules! fcmp {
+            ($variant:ident) => {{
+                let (dest, src1, src2) = (p.reg()?, p.freg()?, p.freg()?);
+                Inst::$variant { dest, src1, src2 }
+            }};
+        }
+        // `dest, src[, rm]` with the register kinds of the destination and source
+        macro_rules! fr2_rm {
+            ($variant:ident, $dest:ident, $src:ident) => {
+                fr2_rm!($variant, $dest, $src, rm)
+            };
+            ($variant:ident, $dest:ident, $src:ident, $rm:ident) => {{
+                let (dest, src) = (p.$dest()?, p.$src()?);
+                let rm = p.$rm()?;
+                Inst::$variant { rm, dest, src }
+            }};
+        }
+        // `dest, src[, v0.t]` vector unary instructions with the register kind of the destination
+        macro_rules! vunary {
+            ($variant:ident, $dest:ident) => {{
+                let (dest, src, masked) = (p.$dest()?, p.vreg()?, p.vmask()?);
+                Inst::$variant { dest, src, masked }
+            }};
+        }
+        // `vzext.vf<factor>` and `vsext.vf<factor>`
+        macro_rules! vext {
+            ($variant:ident, $factor:expr) => {{
+                let (dest, src, masked) = (p.vreg()?, p.vreg()?, p.vmask()?);
+                Inst::$variant { factor: $factor, dest, src, masked }
+            }};
+        }
+        // `dest, src` with the register kinds of the destination and source
+        macro_rules! fr2 {
+            ($variant:ident, $dest:ident, $src:ident) => {{
+                let (dest, src) = (p.$dest()?, p.$src()?);
+                Inst::$variant { dest, src }
+            }};
+        }
+        // `dest, constant` of `fli`
+        macro_rules! fli {
+            ($variant:ident) => {{
+                let (dest, imm) = (p.freg()?, p.fli()?);
+                Inst::$variant { dest, imm }
+            }};
+        }
+
+        let inst = match mnemonic {
+            "lui" | "auipc" => {
+                let dest = p.reg()?;
+                let uimm = Imm::new_i32((p.imm(0..=0xfffff, 1)? << 12) as i32);
+                if mnemonic == "lui" {
+                    Inst::Lui { uimm, dest }
+                } else {
+                    Inst::Auipc { uimm, dest }
+                }
+            }
+            "j" => Inst::Jal {
+                offset: Imm::new_i32(p.imm(-(1 << 20)..=(1 << 20) - 2, 2)? as i32),
+                dest: Reg::ZERO,
+            },
+            "jal" => {
+                let dest = p.reg()?;
+                let offset = Imm::new_i32(p.imm(-(1 << 20)..=(1 << 20) - 2, 2)? as i32);
+                Inst::Jal { offset, dest }
+            }
+            "ret" => Inst::Jalr {
+                offset: Imm::ZERO,
+                base: Reg::RA,
+                dest: Reg::ZERO,
+            },
+            "jalr" => {
+                let dest = p.reg()?;
+                let (offset, base) = p.mem(-2048..=2047, 1)?;
+                Inst::Jalr { offset, base, dest }
+            }
+            "beq" => branch!(Beq),
+            "bne" => branch!(Bne),
+            "blt" => branch!(Blt),
+            "bge" => branch!(Bge),
+            "bltu" => branch!(Bltu),
+            "bgeu" => branch!(Bgeu),
+            "lb" => load!(Lb, reg),
+            "lbu" => load!(Lbu, reg),
+            "lh" => load!(Lh, reg),
+            "lhu" => load!(Lhu, reg),
+            "lw" => load!(Lw, reg),
+            "lwu" => rv64!(load!(Lwu, reg)),
+            "ld" => rv64!(load!(Ld, reg)),
+            "sb" => store!(Sb, reg),
+            "sh" => store!(Sh, reg),
+            "sw" => store!(Sw, reg),
+            "sd" => rv64!(store!(Sd, reg)),
+            "nop" => Inst::Addi {
+                imm: Imm::ZERO,
+                dest: Reg::ZERO,
+                src1: Reg::ZERO,
+            },
+            "li" => {
+                let (dest, imm) = (p.reg()?, p.imm_i()?);
+                Inst::Addi {
+                    imm,
+                    dest,
+                    src1: Reg::ZERO,
+                }
+            }
+            "mv" => {
+                let (dest, src1) = (p.reg()?, p.reg()?);
+                Inst::Addi {
+                    imm: Imm::ZERO,
+                    dest,
+                    src1,
+                }
+            }
+            "addi" => i!(Addi),
+            "sext.w" => {
+                let (dest, src1) = (p.reg()?, p.reg()?);
+                rv64!(Inst::AddiW {
+                    imm: Imm::ZERO,
+                    dest,
+                    src1,
+                })
+            }
+            "addiw" => rv64!(i!(AddiW)),
+            "slti" => i!(Slti),
+            "sltiu" => i!(Sltiu),
+            "xori" => i!(Xori),
+            "ori" => i!(Ori),
+            "andi" => i!(Andi),
+            "slli" => shift!(Slli, xlen_bits),
+            "slliw" => rv64!(shift!(SlliW, 32)),
+            "srli" => shift!(Srli, xlen_bits),
+            "srliw" => rv64!(shift!(SrliW, 32)),
+            "srai" => shift!(Srai, xlen_bits),
+            "sraiw" => rv64!(shift!(SraiW, 32)),
+            "add" => r!(Add),
+            "addw" => rv64!(r!(AddW)),
+            "sub" => r!(Sub),
+            "subw" => rv64!(r!(SubW)),
+            "sll" => r!(Sll),
+            "sllw" => rv64!(r!(SllW)),
+            "slt" => r!(Slt),
+            "sltu" => r!(Sltu),
+            "xor" => r!(Xor),
+            "srl" => r!(Srl),
+            "srlw" => rv64!(r!(SrlW)),
+            "sra" => r!(Sra),
+            "sraw" => rv64!(r!(SraW)),
+            "or" => r!(Or),
+            "and" => r!(And),
+            "fence" => {
+                let (pred, succ) = if p.has_operand() {
+                    (p.fence_set()?, p.fence_set()?)
+                } else {
+                    let all = FenceSet {
+                        device_input: true,
+                        device_output: true,
+                        memory_read: true,
+                        memory_write: true,
+                    };
+                    (all, all)
+                };
+                Inst::Fence {
+                    fence: Fence {
+                        fm: 0b0000,
+                        pred,
+                        succ,
+                        dest: Reg::ZERO,
+                        src: Reg::ZERO,
+                    },
+                }
+            }
+            "fence.tso" | "pause" => {
+                let none = FenceSet {
+                    device_input: false,
+                    device_output: false,
+                    memory_read: false,
+                    memory_write: false,
+                };
+                let rw = FenceSet {
+                    memory_read: true,
+                    memory_write: true,
+                    ..none
+                };
+                let w = FenceSet {
+                    memory_write: true,
+                    ..none
+                };
+                let (fm, pred, succ) = if mnemonic == "pause" {
+                    (0b0000, w, none)
+                } else {
+                    (0b1000, rw, rw)
+                };
+                Inst::Fence {
+                    fence: Fence {
+                        fm,
+                        pred,
+                        succ,
+                        dest: Reg::ZERO,
+                        src: Reg::ZERO,
+                    },
+                }
+            }
+            "fence.i" => Inst::FenceI,
+            "ecall" => Inst::Ecall,
+            "ebreak" => Inst::Ebreak,
+            "mret" => Inst::Mret,
+            "sret" => Inst::Sret,
+            "wfi" => Inst::Wfi,
+            "sfence.vma" => fence_vma!(SfenceVma, vaddr, asid),
+            "hfence.vvma" => fence_vma!(HfenceVvma, vaddr, asid),
+            "hfence.gvma" => fence_vma!(HfenceGvma, gaddr, vmid),
+            "mul" => r!(Mul),
+            "mulw" => rv64!(r!(MulW)),
+            "mulh" => r!(Mulh),
+            "mulhsu" => r!(Mulhsu),
+            "mulhu" => r!(Mulhu),
+            "div" => r!(Div),
+            "divw" => rv64!(r!(DivW)),
+            "divu" => r!(Divu),
+            "divuw" => rv64!(r!(DivuW)),
+            "rem" => r!(Rem),
+            "remw" => rv64!(r!(RemW)),
+            "remu" => r!(Remu),
+            "remuw" => rv64!(r!(RemuW)),
+            "lr.w" => {
+                let (dest, addr) = (p.reg()?, p.amo_addr()?);
+                Inst::LrW { order, dest, addr }
+            }
+            "sc.w" => {
+                let (dest, src, addr) = (p.reg()?, p.reg()?, p.amo_addr()?);
+                Inst::ScW {
+                    order,
+                    dest,
+                    addr,
+                    src,
+                }
+            }
+            "amoswap.w" => amo!(AmoW, Swap),
+            "amoadd.w" => amo!(AmoW, Add),
+            "amoxor.w" => amo!(AmoW, Xor),
+            "amoand.w" => amo!(AmoW, And),
+            "amoor.w" => amo!(AmoW, Or),
+            "amomin.w" => amo!(AmoW, Min),
+            "amomax.w" => amo!(AmoW, Max),
+            "amominu.w" => amo!(AmoW, Minu),
+            "amomaxu.w" => amo!(AmoW, Maxu),
+            "lr.d" => {
+                let (dest, addr) = (p.reg()?, p.amo_addr()?);
+                rv64!(Inst::LrD { order, dest, addr })
+            }
+            "sc.d" => {
+                let (dest, src, addr) = (p.reg()?, p.reg()?, p.amo_addr()?);
+                rv64!(Inst::ScD {
+                    order,
+                    dest,
+                    addr,
+                    src,
+                })
+            }
+            "amoswap.d" => rv64!(amo!(AmoD, Swap)),
+            "amoadd.d" => rv64!(amo!(AmoD, Add)),
+            "amoxor.d" => rv64!(amo!(AmoD, Xor)),
+            "amoand.d" => rv64!(amo!(AmoD, And)),
+            "amoor.d" => rv64!(amo!(AmoD, Or)),
+            "amomin.d" => rv64!(amo!(AmoD, Min)),
+            "amomax.d" => rv64!(amo!(AmoD, Max)),
+            "amominu.d" => rv64!(amo!(AmoD, Minu)),
+            "amomaxu.d" => rv64!(amo!(AmoD, Maxu)),
+            "amoswap.b" => amo!(AmoB, Swap),
+            "amoadd.b" => amo!(AmoB, Add),
+            "amoxor.b" => amo!(AmoB, Xor),
+            "amoand.b" => amo!(AmoB, And),
+            "amoor.b" => amo!(AmoB, Or),
+            "amomin.b" => amo!(AmoB, Min),
+            "amomax.b" => amo!(AmoB, Max),
+            "amominu.b" => amo!(AmoB, Minu),
+            "amomaxu.b" => amo!(AmoB, Maxu),
+            "amoswap.h" => amo!(AmoH, Swap),
+            "amoadd.h" => amo!(AmoH, Add),
+            "amoxor.h" => amo!(AmoH, Xor),
+            "amoand.h" => amo!(AmoH, And),
+            "amoor.h" => amo!(AmoH, Or),
+            "amomin.h" => amo!(AmoH, Min),
+            "amomax.h" => amo!(AmoH, Max),
+            "amominu.h" => amo!(AmoH, Minu),
+            "amomaxu.h" => amo!(AmoH, Maxu),
+            "amocas.b" => amocas!(AmocasB, false),
+            "amocas.h" => amocas!(AmocasH, false),
+            "amocas.w" => amocas!(AmocasW, false),
+            "amocas.d" => amocas!(AmocasD, xlen.is_32()),
+            "amocas.q" => rv64!(amocas!(AmocasQ, true)),
+            "csrrw" => csr!(Csrrw),
+            "csrrs" => csr!(Csrrs),
+            "csrrc" => csr!(Csrrc),
+            "csrrwi" => csri!(Csrrwi),
+            "csrrsi" => csri!(Csrrsi),
+            "csrrci" => csri!(Csrrci),
+            "csrr" => {
+                let (dest, csr) = (p.reg()?, p.csr()?);
+                Inst::Csrrs {
+                    csr,
+                    dest,
+                    src: Reg::ZERO,
+                }
+            }
+            "csrw" | "csrs" | "csrc" => {
+                let (csr, src) = (p.csr()?, p.reg()?);
+                let dest = Reg::ZERO;
+                match mnemonic {
+                    "csrw" => Inst::Csrrw { csr, dest, src },
+                    "csrs" => Inst::Csrrs { csr, dest, src },
+                    _ => Inst::Csrrc { csr, dest, src },
+                }
+            }
+            "csrwi" | "csrsi" | "csrci" => {
+                let csr = p.csr()?;
+                let uimm = Imm::new_u32(p.imm(0..=31, 1)? as u32);
+                let dest = Reg::ZERO;
+                match mnemonic {
+                    "csrwi" => Inst::Csrrwi { csr, dest, uimm },
+                    "csrsi" => Inst::Csrrsi { csr, dest, uimm },
+                    _ => Inst::Csrrci { csr, dest, uimm },
+                }
+            }
+            "rdcycle" | "rdtime" | "rdinstret" | "rdcycleh" | "rdtimeh" | "rdinstreth" | "frcsr"
+            | "frrm" | "frflags" => Inst::Csrrs {
+                csr: match mnemonic {
+                    "rdcycle" => Csr::CYCLE,
+                    "rdtime" => Csr::TIME,
+                    "rdinstret" => Csr::INSTRET,
+                    "rdcycleh" => Csr::CYCLEH,
+                    "rdtimeh" => Csr::TIMEH,
+                    "rdinstreth" => Csr::INSTRETH,
+                    "frcsr" => Csr::FCSR,
+                    "frrm" => Csr::FRM,
+                    _ => Csr::FFLAGS,
+                },
+                dest: p.reg()?,
+                src: Reg::ZERO,
+            },
+            "fscsr" | "fsrm" | "fsflags" => {
+                let (dest, src) = (p.optional_dest(1)?, p.reg()?);
+                let csr = match mnemonic {
+                    "fscsr" => Csr::FCSR,
+                    "fsrm" => Csr::FRM,
+                    _ => Csr::FFLAGS,
+                };
+                Inst::Csrrw { csr, dest, src }
+            }
+            "fsrmi" | "fsflagsi" => {
+                let dest = p.optional_dest(1)?;
+                let uimm = Imm::new_u32(p.imm(0..=31, 1)? as u32);
+                let csr = if mnemonic == "fsrmi" { Csr::FRM } else { Csr::FFLAGS };
+                Inst::Csrrwi { csr, dest, uimm }
+            }
+
+            "flw" => load!(Flw, freg),
+            "fsw" => store!(Fsw, freg),
+            "fmadd.s" => fr4!(FmaddS),
+            "fmsub.s" => fr4!(FmsubS),
+            "fnmsub.s" => fr4!(FnmsubS),
+            "fnmadd.s" => fr4!(FnmaddS),
+            "fadd.s" => fr3_rm!(FaddS),
+            "fsub.s" => fr3_rm!(FsubS),
+            "fmul.s" => fr3_rm!(FmulS),
+            "fdiv.s" => fr3_rm!(FdivS),
+            "fsqrt.s" => fr2_rm!(FsqrtS, freg, freg),
+            "fsgnj.s" => fr3!(FsgnjS),
+            "fsgnjn.s" => fr3!(FsgnjnS),
+            "fsgnjx.s" => fr3!(FsgnjxS),
+            "fmin.s" => fr3!(FminS),
+            "fmax.s" => fr3!(FmaxS),
+            "fli.s" => fli!(FliS),
+            "fminm.s" => fr3!(FminmS),
+            "fmaxm.s" => fr3!(FmaxmS),
+            "fround.s" => fr2_rm!(FroundS, freg, freg),
+            "froundnx.s" => fr2_rm!(FroundnxS, freg, freg),
+            "fltq.s" => fcmp!(FltqS),
+            "fleq.s" => fcmp!(FleqS),
+            "fcvt.w.s" => fr2_rm!(FcvtWS, reg, freg),
+            "fcvt.wu.s" => fr2_rm!(FcvtWuS, reg, freg),
+            "fmv.x.w" => fr2!(FmvXW, reg, freg),
+            "feq.s" => fcmp!(FeqS),
+            "flt.s" => fcmp!(FltS),
+            "fle.s" => fcmp!(FleS),
+            "fclass.s" => fr2!(FclassS, reg, freg),
+            "fcvt.s.w" => fr2_rm!(FcvtSW, freg, reg),
+            "fcvt.s.wu" => fr2_rm!(FcvtSWu, freg, reg),
+            "fmv.w.x" => fr2!(FmvWX, freg, reg),
+
+            "fld" => load!(Fld, freg),
+            "fsd" => store!(Fsd, freg),
+            "fmadd.d" => fr4!(FmaddD),
+            "fmsub.d" => fr4!(FmsubD),
+            "fnmsub.d" => fr4!(FnmsubD),
+            "fnmadd.d" => fr4!(FnmaddD),
+            "fadd.d" => fr3_rm!(FaddD),
+            "fsub.d" => fr3_rm!(FsubD),
+            "fmul.d" => fr3_rm!(FmulD),
+            "fdiv.d" => fr3_rm!(FdivD),
+            "fsqrt.d" => fr2_rm!(FsqrtD, freg, freg),
+            "fsgnj.d" => fr3!(FsgnjD),
+            "fsgnjn.d" => fr3!(FsgnjnD),
+            "fsgnjx.d" => fr3!(FsgnjxD),
+            "fmin.d" => fr3!(FminD),
+            "fmax.d" => fr3!(FmaxD),
+            "fli.d" => fli!(FliD),
+            "fminm.d" => fr3!(FminmD),
+            "fmaxm.d" => fr3!(FmaxmD),
+            "fround.d" => fr2_rm!(FroundD, freg, freg),
+            "froundnx.d" => fr2_rm!(FroundnxD, freg, freg),
+            "fcvtmod.w.d" => {
+                let (dest, src) = (p.reg()?, p.freg()?);
+                if p.rm()? != RoundingMode::RoundTowardsZero {
+                    return Err(Parser::error(mnemonic_span, "fcvtmod.w.d requires `rtz`"));
+                }
+                Inst::FcvtmodWD { dest, src }
+            }
+            "fltq.d" => fcmp!(FltqD),
+            "fleq.d" => fcmp!(FleqD),
+            "fmvh.x.d" => rv32!(fr2!(FmvhXD, reg, freg)),
+            "fmvp.d.x" => {
+                let (dest, src1, src2) = (p.freg()?, p.reg()?, p.reg()?);
+                rv32!(Inst::FmvpDX { dest, src1, src2 })
+            }
+            "fcvt.s.d" => fr2_rm!(FcvtSD, freg, freg),
+            "fcvt.d.s" => fr2_rm!(FcvtDS, freg, freg),
+            "feq.d" => fcmp!(FeqD),
+            "flt.d" => fcmp!(FltD),
+            "fle.d" => fcmp!(FleD),
+            "fclass.d" => fr2!(FclassD, reg, freg),
+            "fcvt.w.d" => fr2_rm!(FcvtWD, reg, freg),
+            "fcvt.wu.d" => fr2_rm!(FcvtWuD, reg, freg),
+            "fcvt.d.w" => fr2_rm!(FcvtDW, freg, reg),
+            "fcvt.d.wu" => fr2_rm!(FcvtDWu, freg, reg),
+
+            "fcvt.l.s" => rv64!(fr2_rm!(FcvtLS, reg, freg)),
+            "fcvt.lu.s" => rv64!(fr2_rm!(FcvtLuS, reg, freg)),
+            "fcvt.s.l" => rv64!(fr2_rm!(FcvtSL, freg, reg)),
+            "fcvt.s.lu" => rv64!(fr2_rm!(FcvtSLu, freg, reg)),
+            "fcvt.l.d" => rv64!(fr2_rm!(FcvtLD, reg, freg)),
+            "fcvt.lu.d" => rv64!(fr2_rm!(FcvtLuD, reg, freg)),
+            "fmv.x.d" => rv64!(fr2!(FmvXD, reg, freg)),
+            "fcvt.d.l" => rv64!(fr2_rm!(FcvtDL, freg, reg)),
+            "fcvt.d.lu" => rv64!(fr2_rm!(FcvtDLu, freg, reg)),
+            "fmv.d.x" => rv64!(fr2!(FmvDX, freg, reg)),
+
+            "flh" => load!(Flh, freg),
+            "fsh" => store!(Fsh, freg),
+            "fmadd.h" => fr4!(FmaddH),
+            "fmsub.h" => fr4!(FmsubH),
+            "fnmsub.h" => fr4!(FnmsubH),
+            "fnmadd.h" => fr4!(FnmaddH),
+            "fadd.h" => fr3_rm!(FaddH),
+            "fsub.h" => fr3_rm!(FsubH),
+            "fmul.h" => fr3_rm!(FmulH),
+            "fdiv.h" => fr3_rm!(FdivH),
+            "fsqrt.h" => fr2_rm!(FsqrtH, freg, freg),
+            "fsgnj.h" => fr3!(FsgnjH),
+            "fsgnjn.h" => fr3!(FsgnjnH),
+            "fsgnjx.h" => fr3!(FsgnjxH),
+            "fmin.h" => fr3!(FminH),
+            "fmax.h" => fr3!(FmaxH),
+            "fli.h" => fli!(FliH),
+            "fminm.h" => fr3!(FminmH),
+            "fmaxm.h" => fr3!(FmaxmH),
+            "fround.h" => fr2_rm!(FroundH, freg, freg),
+            "froundnx.h" => fr2_rm!(FroundnxH, freg, freg),
+            "fltq.h" => fcmp!(FltqH),
+            "fleq.h" => fcmp!(FleqH),
+            "fcvt.s.h" => fr2_rm!(FcvtSH, freg, freg, exact_rm),
+            "fcvt.h.s" => fr2_rm!(FcvtHS, freg, freg),
+            "fcvt.d.h" => fr2_rm!(FcvtDH, freg, freg, exact_rm),
+            "fcvt.h.d" => fr2_rm!(FcvtHD, freg, freg),
+            "feq.h" => fcmp!(FeqH),
+            "flt.h" => fcmp!(FltH),
+            "fle.h" => fcmp!(FleH),
+            "fclass.h" => fr2!(FclassH, reg, freg),
+            "fcvt.w.h" => fr2_rm!(FcvtWH, reg, freg),
+            "fcvt.wu.h" => fr2_rm!(FcvtWuH, reg, freg),
+            "fcvt.h.w" => fr2_rm!(FcvtHW, freg, reg),
+            "fcvt.h.wu" => fr2_rm!(FcvtHWu, freg, reg),
+            "fmv.x.h" => fr2!(FmvXH, reg, freg),
+            "fmv.h.x" => fr2!(FmvHX, freg, reg),
+            "fcvt.l.h" => rv64!(fr2_rm!(FcvtLH, reg, freg)),
+            "fcvt.lu.h" => rv64!(fr2_rm!(FcvtLuH, reg, freg)),
+            "fcvt.h.l" => rv64!(fr2_rm!(FcvtHL, freg, reg)),
+            "fcvt.h.lu" => rv64!(fr2_rm!(FcvtHLu, freg, reg)),
+
+            "flq" => load!(Flq, freg),
+            "fsq" => store!(Fsq, freg),
+            "fmadd.q" => fr4!(FmaddQ),
+            "fmsub.q" => fr4!(FmsubQ),
+            "fnmsub.q" => fr4!(FnmsubQ),
+            "fnmadd.q" => fr4!(FnmaddQ),
+            "fadd.q" => fr3_rm!(FaddQ),
+            "fsub.q" => fr3_rm!(FsubQ),
+            "fmul.q" => fr3_rm!(FmulQ),
+            "fdiv.q" => fr3_rm!(FdivQ),
+            "fsqrt.q" => fr2_rm!(FsqrtQ, freg, freg),
+            "fsgnj.q" => fr3!(FsgnjQ),
+            "fsgnjn.q" => fr3!(FsgnjnQ),
+            "fsgnjx.q" => fr3!(FsgnjxQ),
+            "fmin.q" => fr3!(FminQ),
+            "fmax.q" => fr3!(FmaxQ),
+            "fcvt.s.q" => fr2_rm!(FcvtSQ, freg, freg),
+            "fcvt.q.s" => fr2_rm!(FcvtQS, freg, freg),
+            "fcvt.d.q" => fr2_rm!(FcvtDQ, freg, freg),
+            "fcvt.q.d" => fr2_rm!(FcvtQD, freg, freg),
+            "fcvt.h.q" => fr2_rm!(FcvtHQ, freg, freg),
+            "fcvt.q.h" => fr2_rm!(FcvtQH, freg, freg),
+            "feq.q" => fcmp!(FeqQ),
+            "flt.q" => fcmp!(FltQ),
+            "fle.q" => fcmp!(FleQ),
+            "fclass.q" => fr2!(FclassQ, reg, freg),
+            "fcvt.w.q" => fr2_rm!(FcvtWQ, reg, freg),
+            "fcvt.wu.q" => fr2_rm!(FcvtWuQ, reg, freg),
+            "fcvt.q.w" => fr2_rm!(FcvtQW, freg, reg),
+            "fcvt.q.wu" => fr2_rm!(FcvtQWu, freg, reg),
+            "fcvt.l.q" => rv64!(fr2_rm!(FcvtLQ, reg, freg)),
+            "fcvt.lu.q" => rv64!(fr2_rm!(FcvtLuQ, reg, freg)),
+            "fcvt.q.l" => rv64!(fr2_rm!(FcvtQL, freg, reg)),
+            "fcvt.q.lu" => rv64!(fr2_rm!(FcvtQLu, freg, reg)),
+            "sh1add" => r!(Sh1add),
+            "sh2add" => r!(Sh2add),
+            "sh3add" => r!(Sh3add),
+            "add.uw" => rv64!(r!(AddUw)),
+            "sh1add.uw" => rv64!(r!(Sh1addUw)),
+            "sh2add.uw" => rv64!(r!(Sh2addUw)),
+            "sh3add.uw" => rv64!(r!(Sh3addUw)),
+            "slli.uw" => rv64!(shift!(SlliUw, 64)),
+            "andn" => r!(Andn),
+            "orn" => r!(Orn),
+            "xnor" => r!(Xnor),
+            "clz" => unary!(Clz),
+            "clzw" => rv64!(unary!(ClzW)),
+            "ctz" => unary!(Ctz),
+            "ctzw" => rv64!(unary!(CtzW)),
+            "cpop" => unary!(Cpop),
+            "cpopw" => rv64!(unary!(CpopW)),
+            "max" => r!(Max),
+            "maxu" => r!(Maxu),
+            "min" => r!(Min),
+            "minu" => r!(Minu),
+            "sext.b" => unary!(SextB),
+            "sext.h" => unary!(SextH),
+            "zext.h" => unary!(ZextH),
+            "rol" => r!(Rol),
+            "rolw" => rv64!(r!(RolW)),
+            "ror" => r!(Ror),
+            "rorw" => rv64!(r!(RorW)),
+            "rori" => shift!(Rori, xlen_bits),
+            "roriw" => rv64!(shift!(RoriW, 32)),
+            "orc.b" => unary!(OrcB),
+            "rev8" => unary!(Rev8),
+            "clmul" => r!(Clmul),
+            "clmulh" => r!(Clmulh),
+            "clmulr" => r!(Clmulr),
+            "bclr" => r!(Bclr),
+            "bclri" => shift!(Bclri, xlen_bits),
+            "bext" => r!(Bext),
+            "bexti" => shift!(Bexti, xlen_bits),
+            "binv" => r!(Binv),
+            "binvi" => shift!(Binvi, xlen_bits),
+            "bset" => r!(Bset),
+            "bseti" => shift!(Bseti, xlen_bits),
+            "czero.eqz" => r!(CzeroEqz),
+            "czero.nez" => r!(CzeroNez),
+            "cbo.clean" => Inst::CboClean { base: p.amo_addr()? },
+            "cbo.flush" => Inst::CboFlush { base: p.amo_addr()? },
+            "cbo.inval" => Inst::CboInval { base: p.amo_addr()? },
+            "cbo.zero" => Inst::CboZero { base: p.amo_addr()? },
+            "prefetch.i" | "prefetch.r" | "prefetch.w" => {
+                let (offset, base) = p.mem(-2048..=2016, 32)?;
+                match mnemonic {
+                    "prefetch.i" => Inst::PrefetchI { offset, base },
+                    "prefetch.r" => Inst::PrefetchR { offset, base },
+                    _ => Inst::PrefetchW { offset, base },
+                }
+            }
+
+            "vsetvli" => {
+                let (dest, avl, vtype) = (p.reg()?, p.reg()?, p.vtype(11)?);
+                Inst::Vsetvli { dest, avl, vtype }
+            }
+            "vsetivli" => {
+                let dest = p.reg()?;
+                let avl = Imm::new_u32(p.imm(0..=31, 1)? as u32);
+                let vtype = p.vtype(10)?;
+                Inst::Vsetivli { dest, avl, vtype }
+            }
+            "vsetvl" => {
+                let (dest, avl, vtype) = (p.reg()?, p.reg()?, p.reg()?);
+                Inst::Vsetvl { dest, avl, vtype }
+            }
+            "vlm.v" => {
+                let (dest, base) = (p.vreg()?, p.amo_addr()?);
+                Inst::Vlm { dest, base }
+            }
+            "vsm.v" => {
+                let (src, base) = (p.vreg()?, p.amo_addr()?);
+                Inst::Vsm { src, base }
+            }
+            "vmv.v.v" | "vmv.v.x" | "vmv.v.i" | "vfmv.v.f" => {
+                let (dest, src2, masked) = (p.vreg()?, VReg(0), false);
+                match mnemonic {
+                    "vmv.v.v" => Inst::Opivv { op: OpiFunct6::Vmerge, dest, src2, src1: p.vreg()?, masked },
+                    "vmv.v.x" => Inst::Opivx { op: OpiFunct6::Vmerge, dest, src2, src1: p.reg()?, masked },
+                    "vmv.v.i" => {
+                        let imm = Imm::new_i32(p.imm(-16..=15, 1)? as i32);
+                        Inst::Opivi { op: OpiFunct6::Vmerge, dest, src2, imm, masked }
+                    }
+                    _ => Inst::Opfvf { op: OpfFunct6::Vfmerge, dest, src2, src1: p.freg()?, masked },
+                }
+            }
+            "vmv1r.v" | "vmv2r.v" | "vmv4r.v" | "vmv8r.v" => {
+                let (dest, src) = (p.vreg()?, p.vreg()?);
+                Inst::VmvNrR { nr: mnemonic.as_bytes()[3] - b'0', dest, src }
+            }
+            "vmv.x.s" => fr2!(VmvXS, reg, vreg),
+            "vmv.s.x" => fr2!(VmvSX, vreg, reg),
+            "vcpop.m" => vunary!(VcpopM, reg),
+            "vfirst.m" => vunary!(VfirstM, reg),
+            "vzext.vf2" => vext!(Vzext, 2),
+            "vzext.vf4" => vext!(Vzext, 4),
+            "vzext.vf8" => vext!(Vzext, 8),
+            "vsext.vf2" => vext!(Vsext, 2),
+            "vsext.vf4" => vext!(Vsext, 4),
+            "vsext.vf8" => vext!(Vsext, 8),
+            "vmsbf.m" => vunary!(VmsbfM, vreg),
+            "vmsof.m" => vunary!(VmsofM, vreg),
+            "vmsif.m" => vunary!(VmsifM, vreg),
+            "viota.m" => vunary!(ViotaM, vreg),
+            "vid.v" => {
+                let (dest, masked) = (p.vreg()?, p.vmask()?);
+                Inst::VidV { dest, masked }
+            }
+            "vfmv.f.s" => fr2!(VfmvFS, freg, vreg),
+            "vfmv.s.f" => fr2!(VfmvSF, vreg, freg),
+            _ => match p.vector(mnemonic)? {
+                Some(inst) => inst,
+                None => return Err(Parser::error(mnemonic_span, "unknown instruction")),
+            },
+        };
+        p.finish()?;
+        Ok(inst)
+    }
+}
@@ -0,0 +1,1132 @@
+//! Typed views of the values of well-known CSRs.
+
+use core::fmt::{self, Display};
+
+use crate::{PrivilegeLevel, RoundingMode, Xlen};
+
+/// The bits of `xlen`.
+fn xlen_bits(xlen: Xlen) -> u32 {
+    match xlen {
+        Xlen::Rv32 => 32,
+        Xlen::Rv64 => 64,
+    }
+}
+
+/// Truncates `value` to `xlen` bits.
+fn truncate(value: u64, xlen: Xlen) -> u64 {
+    match xlen {
+        Xlen::Rv32 => value & u64::from(u32::MAX),
+        Xlen::Rv64 => value,
+    }
+}
+
+/// The `width` bits of `value` starting at bit `lo`.
+fn field(value: u64, lo: u32, width: u32) -> u64 {
+    (value >> lo) & ((1 << width) - 1)
+}
+
+/// Replaces the `width` bits of `value` starting at bit `lo`, ignoring excess bits of `field`.
+fn set_field(value: &mut u64, lo: u32, width: u32, field: u64) {
+    let mask = ((1 << width) - 1) << lo;
+    *value = (*value & !mask) | ((field << lo) & mask);
+}
+
+/// Generates a getter and a setter for each single-bit field of the `value`.
+macro_rules! flags {
+    ($($(#[$meta:meta])* $name:ident, $set:ident: $bit:literal;)*) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(&self) -> bool {
+                (self.value >> $bit) & 1 != 0
+            }
+
+            #[doc = concat!("Sets [`Self::", stringify!($name), "`].")]
+            pub fn $set(&mut self, value: bool) {
+                if value {
+                    self.value |= 1 << $bit;
+                } else {
+                    self.value &= !(1 << $bit);
+                }
+            }
+        )*
+    };
+}
+
+/// Generates the enums of trap causes, which are identified by their code in `mcause`.
+macro_rules! trap_causes {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($variant:ident = $code:literal, $description:literal;)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $(
+                #[doc = concat!("Code ", $code, ": ", $description)]
+                $variant,
+            )*
+        }
+
+        impl $name {
+            /// All causes, ordered by their code.
+            pub const ALL: &[Self] = &[$(Self::$variant),*];
+
+            /// The exception code in `mcause`.
+            pub fn code(self) -> u64 {
+                match self {
+                    $(Self::$variant => $code,)*
+                }
+            }
+
+            /// The cause with the exception code in `mcause`.
+            pub fn from_code(code: u64) -> Option<Self> {
+                match code {
+                    $($code => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(match self {
+                    $(Self::$variant => $description,)*
+                })
+            }
+        }
+    };
+}
+
+trap_causes! {
+    /// The standard interrupts, which are also the bits of `mip` and `mie` (see [`Mip`]).
+    pub enum Interrupt {
+        SupervisorSoftware = 1, "supervisor software interrupt";
+        VirtualSupervisorSoftware = 2, "virtual supervisor software interrupt";
+        MachineSoftware = 3, "machine software interrupt";
+        SupervisorTimer = 5, "supervisor timer interrupt";
+        VirtualSupervisorTimer = 6, "virtual supervisor timer interrupt";
+        MachineTimer = 7, "machine timer interrupt";
+        SupervisorExternal = 9, "supervisor external interrupt";
+        VirtualSupervisorExternal = 10, "virtual supervisor external interrupt";
+        MachineExternal = 11, "machine external interrupt";
+        SupervisorGuestExternal = 12, "supervisor guest external interrupt";
+        CounterOverflow = 13, "counter-overflow interrupt";
+    }
+}
+
+trap_causes! {
+    /// The standard exceptions.
+    pub enum Exception {
+        InstructionAddressMisaligned = 0, "instruction address misaligned";
+        InstructionAccessFault = 1, "instruction access fault";
+        IllegalInstruction = 2, "illegal instruction";
+        Breakpoint = 3, "breakpoint";
+        LoadAddressMisaligned = 4, "load address misaligned";
+        LoadAccessFault = 5, "load access fault";
+        StoreAddressMisaligned = 6, "store/AMO address misaligned";
+        StoreAccessFault = 7, "store/AMO access fault";
+        UserEnvironmentCall = 8, "environment call from U-mode";
+        SupervisorEnvironmentCall = 9, "environment call from S-mode";
+        VirtualSupervisorEnvironmentCall = 10, "environment call from VS-mode";
+        MachineEnvironmentCall = 11, "environment call from M-mode";
+        InstructionPageFault = 12, "instruction page fault";
+        LoadPageFault = 13, "load page fault";
+        StorePageFault = 15, "store/AMO page fault";
+        SoftwareCheck = 18, "software check";
+        HardwareError = 19, "hardware error";
+        InstructionGuestPageFault = 20, "instruction guest-page fault";
+        LoadGuestPageFault = 21, "load guest-page fault";
+        VirtualInstruction = 22, "virtual instruction";
+        StoreGuestPageFault = 23, "store/AMO guest-page fault";
+    }
+}
+
+impl Interrupt {
+    /// The short name of the interrupt, which prefixes the names of the bits in `mip` and `mie`,
+    /// like `MTI` for `MTIP` and `MTIE`.
+    pub fn short_name(self) -> &'static str {
+        match self {
+            Self::SupervisorSoftware => "SSI",
+            Self::VirtualSupervisorSoftware => "VSSI",
+            Self::MachineSoftware => "MSI",
+            Self::SupervisorTimer => "STI",
+            Self::VirtualSupervisorTimer => "VSTI",
+            Self::MachineTimer => "MTI",
+            Self::SupervisorExternal => "SEI",
+            Self::VirtualSupervisorExternal => "VSEI",
+            Self::MachineExternal => "MEI",
+            Self::SupervisorGuestExternal => "SGEI",
+            Self::CounterOverflow => "LCOFI",
+        }
+    }
+}
+
+/// The state of an extension's registers in the `FS`, `VS` and `XS` fields of `mstatus`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContextStatus {
+    /// The extension is disabled, and accessing its state raises an illegal-instruction exception.
+    Off,
+    /// The registers have their initial values.
+    Initial,
+    /// The registers are unchanged since the state was last saved.
+    Clean,
+    /// The registers have been changed since the state was last saved.
+    Dirty,
+}
+
+impl ContextStatus {
+    fn from_bits(bits: u64) -> Self {
+        match bits & 0b11 {
+            0 => Self::Off,
+            1 => Self::Initial,
+            2 => Self::Clean,
+            _ => Self::Dirty,
+        }
+    }
+
+    fn bits(self) -> u64 {
+        match self {
+            Self::Off => 0,
+            Self::Initial => 1,
+            Self::Clean => 2,
+            Self::Dirty => 3,
+        }
+    }
+}
+
+impl Display for ContextStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Off => "off",
+            Self::Initial => "initial",
+            Self::Clean => "clean",
+            Self::Dirty => "dirty",
+        })
+    }
+}
+
+/// The encoding of a [`PrivilegeLevel`] in `mstatus.MPP`, where `0b10` is reserved.
+fn privilege_bits(privilege: PrivilegeLevel) -> u64 {
+    match privilege {
+        PrivilegeLevel::User => 0b00,
+        PrivilegeLevel::Supervisor => 0b01,
+        PrivilegeLevel::Hypervisor => 0b10,
+        PrivilegeLevel::Machine => 0b11,
+    }
+}
+
+/// The value of the machine status register `mstatus` ([`Csr::MSTATUS`](crate::Csr::MSTATUS)).
+///
+/// On RV32, the fields of `mstatush` are not included.
+///
+/// ```rust
+/// use rv_asm::{Mstatus, PrivilegeLevel, Xlen};
+///
+/// let mut mstatus = Mstatus::new(0x1880, Xlen::Rv64);
+/// assert_eq!(mstatus.mpp(), PrivilegeLevel::Machine);
+/// assert!(mstatus.mpie());
+/// mstatus.set_mie(true);
+/// assert_eq!(mstatus.value(), 0x1888);
+/// assert_eq!(mstatus.to_string(), "MIE MPIE MPP=machine");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mstatus {
+    value: u64,
+    xlen: Xlen,
+}
+
+impl Mstatus {
+    /// Creates the view of an `mstatus` value, which is truncated to `xlen` bits.
+    pub fn new(value: u64, xlen: Xlen) -> Self {
+        Self {
+            value: truncate(value, xlen),
+            xlen,
+        }
+    }
+
+    /// The raw value.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// The register size of the value.
+    pub fn xlen(&self) -> Xlen {
+        self.xlen
+    }
+
+    flags! {
+        /// Supervisor interrupt enable (`SIE`)
+        sie, set_sie: 1;
+        /// Machine interrupt enable (`MIE`)
+        mie, set_mie: 3;
+        /// Supervisor previous interrupt enable (`SPIE`)
+        spie, set_spie: 5;
+        /// User mode big-endian memory accesses (`UBE`)
+        ube, set_ube: 6;
+        /// Machine previous interrupt enable (`MPIE`)
+        mpie, set_mpie: 7;
+        /// Modify privilege of loads and stores to `MPP` (`MPRV`)
+        mprv, set_mprv: 17;
+        /// Permit supervisor user memory access (`SUM`)
+        sum, set_sum: 18;
+        /// Make executable readable (`MXR`)
+        mxr, set_mxr: 19;
+        /// Trap virtual memory management in S-mode (`TVM`)
+        tvm, set_tvm: 20;
+        /// Timeout wait, which traps `wfi` below M-mode (`TW`)
+        tw, set_tw: 21;
+        /// Trap `sret` in S-mode (`TSR`)
+        tsr, set_tsr: 22;
+    }
+
+    /// Supervisor previous privilege (`SPP`), [`PrivilegeLevel::User`] or
+    /// [`PrivilegeLevel::Supervisor`].
+    pub fn spp(&self) -> PrivilegeLevel {
+        match field(self.value, 8, 1) {
+            0 => PrivilegeLevel::User,
+            _ => PrivilegeLevel::Supervisor,
+        }
+    }
+
+    /// Sets [`Self::spp`], where levels above user mode are written as supervisor mode.
+    pub fn set_spp(&mut self, privilege: PrivilegeLevel) {
+        set_field(&mut self.value, 8, 1, (privilege != PrivilegeLevel::User).into());
+    }
+
+    /// Machine previous privilege (`MPP`), where the reserved encoding `0b10` is
+    /// [`PrivilegeLevel::Hypervisor`].
+    pub fn mpp(&self) -> PrivilegeLevel {
+        match field(self.value, 11, 2) {
+            0b00 => PrivilegeLevel::User,
+            0b01 => PrivilegeLevel::Supervisor,
+            0b10 => PrivilegeLevel::Hypervisor,
+            _ => PrivilegeLevel::Machine,
+        }
+    }
+
+    /// Sets [`Self::mpp`].
+    pub fn set_mpp(&mut self, privilege: PrivilegeLevel) {
+        set_field(&mut self.value, 11, 2, privilege_bits(privilege));
+    }
+
+    /// The state of the vector registers (`VS`).
+    pub fn vs(&self) -> ContextStatus {
+        ContextStatus::from_bits(field(self.value, 9, 2))
+    }
+
+    /// Sets [`Self::vs`].
+    pub fn set_vs(&mut self, status: ContextStatus) {
+        set_field(&mut self.value, 9, 2, status.bits());
+    }
+
+    /// The state of the floating-point registers (`FS`).
+    pub fn fs(&self) -> ContextStatus {
+        ContextStatus::from_bits(field(self.value, 13, 2))
+    }
+
+    /// Sets [`Self::fs`].
+    pub fn set_fs(&mut self, status: ContextStatus) {
+        set_field(&mut self.value, 13, 2, status.bits());
+    }
+
+    /// The summarized state of additional user-mode extensions (`XS`).
+    pub fn xs(&self) -> ContextStatus {
+        ContextStatus::from_bits(field(self.value, 15, 2))
+    }
+
+    /// Sets [`Self::xs`].
+    pub fn set_xs(&mut self, status: ContextStatus) {
+        set_field(&mut self.value, 15, 2, status.bits());
+    }
+
+    /// Whether any of `FS`, `VS` or `XS` is dirty (`SD`), the most significant bit.
+    pub fn sd(&self) -> bool {
+        field(self.value, xlen_bits(self.xlen) - 1, 1) != 0
+    }
+
+    /// Sets [`Self::sd`].
+    pub fn set_sd(&mut self, sd: bool) {
+        set_field(&mut self.value, xlen_bits(self.xlen) - 1, 1, sd.into());
+    }
+
+    /// The register size of U-mode (`UXL`), which only exists on RV64.
+    ///
+    /// Returns `None` on RV32 and for RV128 or the reserved encoding.
+    pub fn uxl(&self) -> Option<Xlen> {
+        self.xl(32)
+    }
+
+    /// The register size of S-mode (`SXL`), which only exists on RV64.
+    ///
+    /// Returns `None` on RV32 and for RV128 or the reserved encoding.
+    pub fn sxl(&self) -> Option<Xlen> {
+        self.xl(34)
+    }
+
+    fn xl(&self, lo: u32) -> Option<Xlen> {
+        match (self.xlen, field(self.value, lo, 2)) {
+            (Xlen::Rv64, 1) => Some(Xlen::Rv32),
+            (Xlen::Rv64, 2) => Some(Xlen::Rv64),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Mstatus {
+    /// Lists the bits that are set, and the privilege and context status fields,
+    /// like `MIE MPIE MPP=machine FS=dirty SD`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags = [
+            (self.sie(), "SIE"),
+            (self.mie(), "MIE"),
+            (self.spie(), "SPIE"),
+            (self.ube(), "UBE"),
+            (self.mpie(), "MPIE"),
+            (self.spp() == PrivilegeLevel::Supervisor, "SPP"),
+        ];
+        for (_, name) in flags.iter().filter(|(set, _)| *set) {
+            write!(f, "{name} ")?;
+        }
+        write!(f, "MPP={}", self.mpp())?;
+        for (status, name) in [(self.vs(), "VS"), (self.fs(), "FS"), (self.xs(), "XS")] {
+            if status != ContextStatus::Off {
+                write!(f, " {name}={status}")?;
+            }
+        }
+        let flags = [
+            (self.mprv(), "MPRV"),
+            (self.sum(), "SUM"),
+            (self.mxr(), "MXR"),
+            (self.tvm(), "TVM"),
+            (self.tw(), "TW"),
+            (self.tsr(), "TSR"),
+            (self.sd(), "SD"),
+        ];
+        for (_, name) in flags.iter().filter(|(set, _)| *set) {
+            write!(f, " {name}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The value of the machine ISA register `misa` ([`Csr::MISA`](crate::Csr::MISA)).
+///
+/// ```rust
+/// use rv_asm::{Misa, Xlen};
+///
+/// let misa = Misa::new(0x8000_0000_0014_1105, Xlen::Rv64);
+/// assert_eq!(misa.mxl(), Some(Xlen::Rv64));
+/// assert!(misa.has_extension('C'));
+/// assert_eq!(misa.to_string(), "RV64IMACSU");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Misa {
+    value: u64,
+    xlen: Xlen,
+}
+
+impl Misa {
+    /// Creates the view of a `misa` value, which is truncated to `xlen` bits.
+    pub fn new(value: u64, xlen: Xlen) -> Self {
+        Self {
+            value: truncate(value, xlen),
+            xlen,
+        }
+    }
+
+    /// The raw value.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// The register size of the value.
+    pub fn xlen(&self) -> Xlen {
+        self.xlen
+    }
+
+    /// The register size of M-mode (`MXL`) in the two most significant bits,
+    /// or `None` for RV128 and if `misa` is not implemented.
+    pub fn mxl(&self) -> Option<Xlen> {
+        match field(self.value, xlen_bits(self.xlen) - 2, 2) {
+            1 => Some(Xlen::Rv32),
+            2 => Some(Xlen::Rv64),
+            _ => None,
+        }
+    }
+
+    /// Sets [`Self::mxl`].
+    pub fn set_mxl(&mut self, mxl: Xlen) {
+        let bits = match mxl {
+            Xlen::Rv32 => 1,
+            Xlen::Rv64 => 2,
+        };
+        set_field(&mut self.value, xlen_bits(self.xlen) - 2, 2, bits);
+    }
+
+    /// The bits of the extensions `A` (bit 0) to `Z` (bit 25).
+    pub fn extensions(&self) -> u32 {
+        field(self.value, 0, 26) as u32
+    }
+
+    /// Whether the bit of the extension `letter` is set, like `'M'` for bit 12.
+    ///
+    /// Letters are case-insensitive, and other characters are never set.
+    pub fn has_extension(&self, letter: char) -> bool {
+        extension_bit(letter).is_some_and(|bit| field(self.value, bit, 1) != 0)
+    }
+
+    /// Sets [`Self::has_extension`], ignoring characters that are not letters.
+    pub fn set_extension(&mut self, letter: char, enabled: bool) {
+        if let Some(bit) = extension_bit(letter) {
+            set_field(&mut self.value, bit, 1, enabled.into());
+        }
+    }
+}
+
+/// The bit of an extension letter in `misa`.
+fn extension_bit(letter: char) -> Option<u32> {
+    letter
+        .is_ascii_alphabetic()
+        .then(|| u32::from(letter.to_ascii_uppercase()) - u32::from('A'))
+}
+
+impl Display for Misa {
+    /// Displays the base and the extensions in the canonical order, like `RV64IMAFDC`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match field(self.value, xlen_bits(self.xlen) - 2, 2) {
+            1 => write!(f, "RV32")?,
+            2 => write!(f, "RV64")?,
+            3 => write!(f, "RV128")?,
+            _ => write!(f, "RV{}", xlen_bits(self.xlen))?,
+        }
+        // The canonical order of ISA strings, followed by the remaining letters
+        const ORDER: &str = "IEMAFDQCBPVH";
+        let rest = ('A'..='Z').filter(|letter| !ORDER.contains(*letter));
+        for letter in ORDER.chars().chain(rest) {
+            if self.has_extension(letter) {
+                write!(f, "{letter}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The value of the machine trap cause register `mcause` ([`Csr::MCAUSE`](crate::Csr::MCAUSE)),
+/// which is also the layout of `scause`.
+///
+/// ```rust
+/// use rv_asm::{Interrupt, Mcause, Xlen};
+///
+/// let mcause = Mcause::new(0x8000_0007, Xlen::Rv32);
+/// assert_eq!(mcause.interrupt(), Some(Interrupt::MachineTimer));
+/// assert_eq!(mcause.to_string(), "machine timer interrupt");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mcause {
+    value: u64,
+    xlen: Xlen,
+}
+
+impl Mcause {
+    /// Creates the view of an `mcause` value, which is truncated to `xlen` bits.
+    pub fn new(value: u64, xlen: Xlen) -> Self {
+        Self {
+            value: truncate(value, xlen),
+            xlen,
+        }
+    }
+
+    /// The cause of `interrupt`.
+    pub fn from_interrupt(interrupt: Interrupt, xlen: Xlen) -> Self {
+        let mut mcause = Self::new(interrupt.code(), xlen);
+        mcause.set_interrupt(true);
+        mcause
+    }
+
+    /// The cause of `exception`.
+    pub fn from_exception(exception: Exception, xlen: Xlen) -> Self {
+        Self::new(exception.code(), xlen)
+    }
+
+    /// The raw value.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// The register size of the value.
+    pub fn xlen(&self) -> Xlen {
+        self.xlen
+    }
+
+    /// Whether the trap was caused by an interrupt, the most significant bit.
+    pub fn is_interrupt(&self) -> bool {
+        field(self.value, xlen_bits(self.xlen) - 1, 1) != 0
+    }
+
+    /// Sets [`Self::is_interrupt`].
+    pub fn set_interrupt(&mut self, interrupt: bool) {
+        set_field(&mut self.value, xlen_bits(self.xlen) - 1, 1, interrupt.into());
+    }
+
+    /// The exception code, which are the bits below the interrupt bit.
+    pub fn code(&self) -> u64 {
+        field(self.value, 0, xlen_bits(self.xlen) - 1)
+    }
+
+    /// Sets [`Self::code`], ignoring the bits that don't fit.
+    pub fn set_code(&mut self, code: u64) {
+        set_field(&mut self.value, 0, xlen_bits(self.xlen) - 1, code);
+    }
+
+    /// The standard interrupt that caused the trap.
+    pub fn interrupt(&self) -> Option<Interrupt> {
+        self.is_interrupt().then(|| Interrupt::from_code(self.code())).flatten()
+    }
+
+    /// The standard exception that caused the trap.
+    pub fn exception(&self) -> Option<Exception> {
+        (!self.is_interrupt()).then(|| Exception::from_code(self.code())).flatten()
+    }
+}
+
+impl Display for Mcause {
+    /// Displays the name of the cause, or its code if it is not a standard cause.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.interrupt(), self.exception()) {
+            (Some(interrupt), _) => write!(f, "{interrupt}"),
+            (_, Some(exception)) => write!(f, "{exception}"),
+            _ if self.is_interrupt() => write!(f, "interrupt {}", self.code()),
+            _ => write!(f, "exception {}", self.code()),
+        }
+    }
+}
+
+/// The address translation scheme in the `MODE` field of `satp`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SatpMode {
+    /// No translation or protection
+    Bare,
+    /// Page-based 32-bit virtual addressing (RV32 only)
+    Sv32,
+    /// Page-based 39-bit virtual addressing (RV64 only)
+    Sv39,
+    /// Page-based 48-bit virtual addressing (RV64 only)
+    Sv48,
+    /// Page-based 57-bit virtual addressing (RV64 only)
+    Sv57,
+    /// Page-based 64-bit virtual addressing (RV64 only)
+    Sv64,
+}
+
+impl Display for SatpMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Bare => "Bare",
+            Self::Sv32 => "Sv32",
+            Self::Sv39 => "Sv39",
+            Self::Sv48 => "Sv48",
+            Self::Sv57 => "Sv57",
+            Self::Sv64 => "Sv64",
+        })
+    }
+}
+
+/// The value of the supervisor address translation and protection register `satp`
+/// ([`Csr::SATP`](crate::Csr::SATP)), which is also the layout of `vsatp`.
+///
+/// The fields are at different positions on RV32 and RV64:
+///
+/// | XLEN | `MODE` | `ASID` | `PPN` |
+/// |------|--------|--------|-------|
+/// | 32   | 31     | 30:22  | 21:0  |
+/// | 64   | 63:60  | 59:44  | 43:0  |
+///
+/// ```rust
+/// use rv_asm::{Satp, SatpMode, Xlen};
+///
+/// let mut satp = Satp::new(0, Xlen::Rv64);
+/// satp.set_mode(SatpMode::Sv39);
+/// satp.set_asid(1);
+/// satp.set_ppn(0x80000);
+/// assert_eq!(satp.value(), 0x8000_1000_0008_0000);
+/// assert_eq!(satp.root_page_table(), 0x8000_0000);
+/// assert_eq!(satp.to_string(), "Sv39 asid=0x1 ppn=0x80000");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Satp {
+    value: u64,
+    xlen: Xlen,
+}
+
+impl Satp {
+    /// Creates the view of a `satp` value, which is truncated to `xlen` bits.
+    pub fn new(value: u64, xlen: Xlen) -> Self {
+        Self {
+            value: truncate(value, xlen),
+            xlen,
+        }
+    }
+
+    /// The raw value.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// The register size of the value.
+    pub fn xlen(&self) -> Xlen {
+        self.xlen
+    }
+
+    /// The positions `(lo, width)` of `MODE`, `ASID` and `PPN`.
+    fn layout(&self) -> [(u32, u32); 3] {
+        match self.xlen {
+            Xlen::Rv32 => [(31, 1), (22, 9), (0, 22)],
+            Xlen::Rv64 => [(60, 4), (44, 16), (0, 44)],
+        }
+    }
+
+    /// The translation scheme (`MODE`), or `None` for reserved and custom modes.
+    pub fn mode(&self) -> Option<SatpMode> {
+        let [(lo, width), ..] = self.layout();
+        match (self.xlen, field(self.value, lo, width)) {
+            (_, 0) => Some(SatpMode::Bare),
+            (Xlen::Rv32, _) => Some(SatpMode::Sv32),
+            (Xlen::Rv64, 8) => Some(SatpMode::Sv39),
+            (Xlen::Rv64, 9) => Some(SatpMode::Sv48),
+            (Xlen::Rv64, 10) => Some(SatpMode::Sv57),
+            (Xlen::Rv64, 11) => Some(SatpMode::Sv64),
+            (Xlen::Rv64, _) => None,
+        }
+    }
+
+    /// Sets [`Self::mode`]. Modes of the other XLEN, like `Sv39` on RV32, leave it unchanged.
+    pub fn set_mode(&mut self, mode: SatpMode) {
+        let bits = match (self.xlen, mode) {
+            (_, SatpMode::Bare) => 0,
+            (Xlen::Rv32, SatpMode::Sv32) => 1,
+            (Xlen::Rv64, SatpMode::Sv39) => 8,
+            (Xlen::Rv64, SatpMode::Sv48) => 9,
+            (Xlen::Rv64, SatpMode::Sv57) => 10,
+            (Xlen::Rv64, SatpMode::Sv64) => 11,
+            _ => return,
+        };
+        let [(lo, width), ..] = self.layout();
+        set_field(&mut self.value, lo, width, bits);
+    }
+
+    /// The address space identifier (`ASID`).
+    pub fn asid(&self) -> u64 {
+        let [_, (lo, width), _] = self.layout();
+        field(self.value, lo, width)
+    }
+
+    /// Sets [`Self::asid`], ignoring the bits that don't fit.
+    pub fn set_asid(&mut self, asid: u64) {
+        let [_, (lo, width), _] = self.layout();
+        set_field(&mut self.value, lo, width, asid);
+    }
+
+    /// The physical page number of the root page table (`PPN`).
+    pub fn ppn(&self) -> u64 {
+        let [.., (lo, width)] = self.layout();
+        field(self.value, lo, width)
+    }
+
+    /// Sets [`Self::ppn`], ignoring the bits that don't fit.
+    pub fn set_ppn(&mut self, ppn: u64) {
+        let [.., (lo, width)] = self.layout();
+        set_field(&mut self.value, lo, width, ppn);
+    }
+
+    /// The physical address of the root page table, [`Self::ppn`] times the 4 KiB page size.
+    pub fn root_page_table(&self) -> u64 {
+        self.ppn() << 12
+    }
+}
+
+impl Display for Satp {
+    /// Displays the fields, like `Sv39 asid=0x1 ppn=0x80000`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mode() {
+            Some(mode) => write!(f, "{mode}")?,
+            None => write!(f, "mode={}", field(self.value, 60, 4))?,
+        }
+        write!(f, " asid={:#x} ppn={:#x}", self.asid(), self.ppn())
+    }
+}
+
+/// The addressing mode of trap vectors in the `MODE` field of `mtvec`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TvecMode {
+    /// All traps set the PC to `BASE`.
+    Direct,
+    /// Interrupts set the PC to `BASE` plus four times the interrupt's code.
+    Vectored,
+}
+
+/// The value of the machine trap-handler base address register `mtvec`
+/// ([`Csr::MTVEC`](crate::Csr::MTVEC)), which is also the layout of `stvec`.
+///
+/// ```rust
+/// use rv_asm::{Interrupt, Mcause, Mtvec, TvecMode, Xlen};
+///
+/// let mtvec = Mtvec::new(0x8000_0101, Xlen::Rv32);
+/// assert_eq!(mtvec.mode(), Some(TvecMode::Vectored));
+/// assert_eq!(mtvec.base(), 0x8000_0100);
+/// let cause = Mcause::from_interrupt(Interrupt::MachineTimer, Xlen::Rv32);
+/// assert_eq!(mtvec.trap_address(cause), 0x8000_011c);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mtvec {
+    value: u64,
+    xlen: Xlen,
+}
+
+impl Mtvec {
+    /// Creates the view of an `mtvec` value, which is truncated to `xlen` bits.
+    pub fn new(value: u64, xlen: Xlen) -> Self {
+        Self {
+            value: truncate(value, xlen),
+            xlen,
+        }
+    }
+
+    /// The raw value.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// The register size of the value.
+    pub fn xlen(&self) -> Xlen {
+        self.xlen
+    }
+
+    /// The addressing mode (`MODE`), or `None` for the reserved modes.
+    pub fn mode(&self) -> Option<TvecMode> {
+        match field(self.value, 0, 2) {
+            0 => Some(TvecMode::Direct),
+            1 => Some(TvecMode::Vectored),
+            _ => None,
+        }
+    }
+
+    /// Sets [`Self::mode`].
+    pub fn set_mode(&mut self, mode: TvecMode) {
+        let bits = match mode {
+            TvecMode::Direct => 0,
+            TvecMode::Vectored => 1,
+        };
+        set_field(&mut self.value, 0, 2, bits);
+    }
+
+    /// The 4-byte aligned base address (`BASE`).
+    pub fn base(&self) -> u64 {
+        self.value & !0b11
+    }
+
+    /// Sets [`Self::base`], ignoring the two lowest bits of `base`.
+    pub fn set_base(&mut self, base: u64) {
+        self.value = truncate(base & !0b11, self.xlen) | field(self.value, 0, 2);
+    }
+
+    /// The address that a trap with `cause` jumps to.
+    pub fn trap_address(&self, cause: Mcause) -> u64 {
+        match self.mode() {
+            Some(TvecMode::Vectored) if cause.is_interrupt() => {
+                truncate(self.base().wrapping_add(4 * cause.code()), self.xlen)
+            }
+            _ => self.base(),
+        }
+    }
+}
+
+impl Display for Mtvec {
+    /// Displays the mode and base, like `vectored 0x80000100`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mode() {
+            Some(TvecMode::Direct) => write!(f, "direct")?,
+            Some(TvecMode::Vectored) => write!(f, "vectored")?,
+            None => write!(f, "mode={}", field(self.value, 0, 2))?,
+        }
+        write!(f, " {:#x}", self.base())
+    }
+}
+
+/// The value of the interrupt registers `mip` and `mie` ([`Csr::MIP`](crate::Csr::MIP) and
+/// [`Csr::MIE`](crate::Csr::MIE)), which have a bit for each [`Interrupt`] at its code.
+///
+/// ```rust
+/// use rv_asm::{Interrupt, Mip};
+///
+/// let mut mip = Mip::new(0);
+/// mip.set(Interrupt::MachineTimer, true);
+/// mip.set(Interrupt::SupervisorExternal, true);
+/// assert_eq!(mip.value(), 0x280);
+/// assert_eq!(mip.to_string(), "MTI SEI");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mip {
+    value: u64,
+}
+
+/// The value of `mie`, which has the same layout as [`Mip`].
+pub type Mie = Mip;
+
+impl Mip {
+    /// Creates the view of an `mip` or `mie` value.
+    pub fn new(value: u64) -> Self {
+        Self { value }
+    }
+
+    /// The raw value.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// Whether the bit of `interrupt` is set, which means pending for `mip` and enabled for `mie`.
+    pub fn get(&self, interrupt: Interrupt) -> bool {
+        field(self.value, interrupt.code() as u32, 1) != 0
+    }
+
+    /// Sets [`Self::get`].
+    pub fn set(&mut self, interrupt: Interrupt, value: bool) {
+        set_field(&mut self.value, interrupt.code() as u32, 1, value.into());
+    }
+}
+
+impl Display for Mip {
+    /// Lists the short names of the interrupts that are set, like `MTI SEI`,
+    /// followed by the remaining bits in hex.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rest = self.value;
+        let mut separator = "";
+        // Ordered by priority, like the `mip` descriptions of the specification
+        let order = [
+            Interrupt::MachineExternal,
+            Interrupt::MachineSoftware,
+            Interrupt::MachineTimer,
+            Interrupt::SupervisorExternal,
+            Interrupt::SupervisorSoftware,
+            Interrupt::SupervisorTimer,
+            Interrupt::SupervisorGuestExternal,
+            Interrupt::VirtualSupervisorExternal,
+            Interrupt::VirtualSupervisorSoftware,
+            Interrupt::VirtualSupervisorTimer,
+            Interrupt::CounterOverflow,
+        ];
+        for interrupt in order.into_iter().filter(|&i| self.get(i)) {
+            write!(f, "{separator}{}", interrupt.short_name())?;
+            rest &= !(1 << interrupt.code());
+            separator = " ";
+        }
+        if rest != 0 || separator.is_empty() {
+            write!(f, "{separator}{rest:#x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The value of the floating-point control and status register `fcsr`
+/// ([`Csr::FCSR`](crate::Csr::FCSR)), which combines `frm` and `fflags`.
+///
+/// ```rust
+/// use rv_asm::{Fcsr, RoundingMode};
+///
+/// let mut fcsr = Fcsr::new(0);
+/// fcsr.set_frm(RoundingMode::RoundTowardsZero);
+/// fcsr.set_nv(true);
+/// assert_eq!(fcsr.value(), 0x30);
+/// assert_eq!(fcsr.to_string(), "rtz NV");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fcsr {
+    value: u32,
+}
+
+impl Fcsr {
+    /// Creates the view of an `fcsr` value.
+    pub fn new(value: u32) -> Self {
+        Self { value }
+    }
+
+    /// The raw value.
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    flags! {
+        /// Inexact (`NX`)
+        nx, set_nx: 0;
+        /// Underflow (`UF`)
+        uf, set_uf: 1;
+        /// Overflow (`OF`)
+        of, set_of: 2;
+        /// Divide by zero (`DZ`)
+        dz, set_dz: 3;
+        /// Invalid operation (`NV`)
+        nv, set_nv: 4;
+    }
+
+    /// The accrued exception flags (`fflags`), bits 4:0.
+    pub fn fflags(&self) -> u32 {
+        self.value & 0x1f
+    }
+
+    /// Sets [`Self::fflags`], ignoring the bits that don't fit.
+    pub fn set_fflags(&mut self, fflags: u32) {
+        self.value = (self.value & !0x1f) | (fflags & 0x1f);
+    }
+
+    /// The dynamic rounding mode (`frm`), bits 7:5,
+    /// or `None` for the reserved values including [`RoundingMode::Dynamic`].
+    pub fn frm(&self) -> Option<RoundingMode> {
+        RoundingMode::from_rm((self.value >> 5) & 0b111)
+            .filter(|rm| *rm != RoundingMode::Dynamic)
+    }
+
+    /// Sets [`Self::frm`].
+    pub fn set_frm(&mut self, frm: RoundingMode) {
+        self.value = (self.value & !(0b111 << 5)) | (frm.to_rm() << 5);
+    }
+}
+
+impl Display for Fcsr {
+    /// Displays the rounding mode and the flags that are set, like `rtz NV OF`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.frm() {
+            Some(frm) => write!(f, "{frm}")?,
+            None => write!(f, "frm={}", (self.value >> 5) & 0b111)?,
+        }
+        let flags = [
+            (self.nv(), "NV"),
+            (self.dz(), "DZ"),
+            (self.of(), "OF"),
+            (self.uf(), "UF"),
+            (self.nx(), "NX"),
+        ];
+        for (_, name) in flags.iter().filter(|(set, _)| *set) {
+            write!(f, " {name}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The address-matching mode in the `A` field of a PMP entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PmpAddressMatching {
+    /// The entry is disabled.
+    Off,
+    /// Top of range: the entry matches from the previous entry's address up to its address.
+    Tor,
+    /// Naturally aligned four-byte region
+    Na4,
+    /// Naturally aligned power-of-two region of at least eight bytes
+    Napot,
+}
+
+/// The configuration of a single PMP entry, one byte of the `pmpcfg` CSRs.
+///
+/// ```rust
+/// use rv_asm::{PmpAddressMatching, Pmpcfg};
+///
+/// // pmp1cfg of a pmpcfg0 value
+/// let cfg = Pmpcfg::from_register(0x1f00, 1);
+/// assert_eq!(cfg.a(), PmpAddressMatching::Napot);
+/// assert!(cfg.r() && cfg.w() && cfg.x());
+/// assert_eq!(cfg.to_string(), "NAPOT RWX");
+/// assert_eq!(cfg.insert_into(0, 3), 0x1f00_0000);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pmpcfg {
+    value: u8,
+}
+
+impl Pmpcfg {
+    /// Creates the view of a PMP entry configuration.
+    pub fn new(value: u8) -> Self {
+        Self { value }
+    }
+
+    /// The configuration of entry `index` in a `pmpcfg` CSR value, its byte at `index`.
+    ///
+    /// Each `pmpcfg` CSR holds four entries on RV32 and eight entries on RV64.
+    /// Indices past the value are zero.
+    pub fn from_register(register: u64, index: usize) -> Self {
+        let Some(shift) = Self::shift(index) else {
+            return Self::new(0);
+        };
+        Self::new((register >> shift) as u8)
+    }
+
+    /// Replaces the configuration of entry `index` in a `pmpcfg` CSR value.
+    /// Indices past the value leave it unchanged.
+    pub fn insert_into(self, register: u64, index: usize) -> u64 {
+        let Some(shift) = Self::shift(index) else {
+            return register;
+        };
+        (register & !(0xff << shift)) | (u64::from(self.value) << shift)
+    }
+
+    /// The shift of entry `index` in a `pmpcfg` CSR value, if it is inside of the value.
+    fn shift(index: usize) -> Option<u32> {
+        let shift = index.checked_mul(8).and_then(|shift| u32::try_from(shift).ok())?;
+        (shift < 64).then_some(shift)
+    }
+
+    /// The raw value.
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+
+    flags! {
+        /// Readable (`R`)
+        r, set_r: 0;
+        /// Writable (`W`)
+        w, set_w: 1;
+        /// Executable (`X`)
+        x, set_x: 2;
+        /// Locked (`L`), which also applies the entry to M-mode
+        l, set_l: 7;
+    }
+
+    /// The address-matching mode (`A`).
+    pub fn a(&self) -> PmpAddressMatching {
+        match (self.value >> 3) & 0b11 {
+            0 => PmpAddressMatching::Off,
+            1 => PmpAddressMatching::Tor,
+            2 => PmpAddressMatching::Na4,
+            _ => PmpAddressMatching::Napot,
+        }
+    }
+
+    /// Sets [`Self::a`].
+    pub fn set_a(&mut self, a: PmpAddressMatching) {
+        let bits = match a {
+            PmpAddressMatching::Off => 0,
+            PmpAddressMatching::Tor => 1,
+            PmpAddressMatching::Na4 => 2,
+            PmpAddressMatching::Napot => 3,
+        };
+        self.value = (self.value & !(0b11 << 3)) | (bits << 3);
+    }
+}
+
+impl Display for Pmpcfg {
+    /// Displays the mode and permissions, like `TOR R-X L`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let a = match self.a() {
+            PmpAddressMatching::Off => "OFF",
+            PmpAddressMatching::Tor => "TOR",
+            PmpAddressMatching::Na4 => "NA4",
+            PmpAddressMatching::Napot => "NAPOT",
+        };
+        let bit = |set, c| if set { c } else { '-' };
+        write!(f, "{a} {}{}{}", bit(self.r(), 'R'), bit(self.w(), 'W'), bit(self.x(), 'X'))?;
+        if self.l() {
+            write!(f, " L")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,501 @@
+//! Configurable disassembly output.
+
+use core::fmt::{self, Debug, Display};
+
+use crate::{Csr, FReg, FenceSet, FliConstant, Inst, Reg, RoundingMode, VReg, Xlen};
+
+/// The assembler syntax the output of an [`InstFormatter`] follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Syntax {
+    /// The syntax of the [`Display`] impl of [`Inst`], like `addi sp, sp, -32`.
+    #[default]
+    Default,
+    /// The syntax of GNU `objdump`, like `addi\tsp,sp,-32`.
+    ///
+    /// `lui` and `auipc` immediates are always printed in hex, like `objdump` does.
+    /// With pseudo-instructions, the aliases of `objdump` like `seqz`, `jr` and `fmv.s` are
+    /// used as well.
+    Gnu,
+    /// The syntax of LLVM `llvm-objdump` and `llvm-mc`, like `addi\tsp, sp, -32`.
+    ///
+    /// CSRs without a name are printed in decimal unless hex immediates are enabled,
+    /// like LLVM does. With pseudo-instructions, the same aliases as for [`Syntax::Gnu`] are used.
+    Llvm,
+}
+
+/// A configurable disassembler for [`Inst`].
+///
+/// The default formatter produces the same output as the [`Display`] impl of [`Inst`].
+///
+/// ```rust
+/// use rv_asm::{Inst, InstFormatter, Syntax, Xlen};
+///
+/// let (inst, _) = Inst::decode(0xfe010113, Xlen::Rv32).unwrap();
+/// assert_eq!(format!("{inst}"), "addi sp, sp, -32");
+///
+/// let formatter = InstFormatter::new().with_abi_names(false).with_hex_immediates(true);
+/// assert_eq!(format!("{}", formatter.display(&inst)), "addi x2, x2, -0x20");
+///
+/// let formatter = InstFormatter::new().with_syntax(Syntax::Gnu);
+/// assert_eq!(format!("{}", formatter.display(&inst)), "addi\tsp,sp,-32");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InstFormatter {
+    abi_names: bool,
+    hex_immediates: bool,
+    pseudo_instructions: bool,
+    syntax: Syntax,
+}
+
+impl InstFormatter {
+    /// A formatter with ABI register names, decimal immediates, pseudo-instructions and the
+    /// [`Syntax::Default`] syntax.
+    pub const fn new() -> Self {
+        Self {
+            abi_names: true,
+            hex_immediates: false,
+            pseudo_instructions: true,
+            syntax: Syntax::Default,
+        }
+    }
+
+    /// Returns this formatter with ABI register names (`a0`, `fa0`) enabled or replaced by
+    /// numeric names (`x10`, `f10`).
+    pub const fn with_abi_names(mut self, abi_names: bool) -> Self {
+        self.abi_names = abi_names;
+        self
+    }
+
+    /// Returns this formatter with immediates printed in hex (`-0x20`) or decimal (`-32`).
+    ///
+    /// CSRs without a name are always printed in hex, except for [`Syntax::Llvm`].
+    pub const fn with_hex_immediates(mut self, hex_immediates: bool) -> Self {
+        self.hex_immediates = hex_immediates;
+        self
+    }
+
+    /// Returns this formatter with pseudo-instructions like `li`, `mv`, `j` and `ret` enabled or
+    /// disabled.
+    ///
+    /// Without pseudo-instructions, the dynamic rounding mode `dyn` is printed as well.
+    pub const fn with_pseudo_instructions(mut self, pseudo_instructions: bool) -> Self {
+        self.pseudo_instructions = pseudo_instructions;
+        self
+    }
+
+    /// Returns this formatter with the syntax changed to `syntax`.
+    pub const fn with_syntax(mut self, syntax: Syntax) -> Self {
+        self.syntax = syntax;
+        self
+    }
+
+    /// Whether registers are printed with their ABI names.
+    pub const fn abi_names(&self) -> bool {
+        self.abi_names
+    }
+
+    /// Whether immediates are printed in hex.
+    pub const fn hex_immediates(&self) -> bool {
+        self.hex_immediates
+    }
+
+    /// Whether pseudo-instructions are used.
+    pub const fn pseudo_instructions(&self) -> bool {
+        self.pseudo_instructions
+    }
+
+    /// The syntax of the output.
+    pub const fn syntax(&self) -> Syntax {
+        self.syntax
+    }
+
+    /// Returns a value that formats `inst` with this formatter when displayed.
+    pub fn display<'a>(&'a self, inst: &'a Inst) -> FormattedInst<'a> {
+        FormattedInst {
+            formatter: self,
+            inst,
+            location: None,
+        }
+    }
+
+    /// Returns a value that formats `inst` at address `pc` with this formatter when displayed.
+    ///
+    /// Branch and jump targets are printed as absolute addresses instead of relative offsets,
+    /// followed by the symbol from `symbols` that contains the target, like `0x1018 <main+0x8>`.
+    /// The address computed by `auipc` is added as a comment, like `auipc a0, 2 # 0x3000 <data>`.
+    /// Addresses wrap around at the end of the address space of `xlen`, and the CSRs that only
+    /// exist on RV32, like `cycleh`, are printed without their name on RV64.
+    ///
+    /// ```rust
+    /// use rv_asm::{Inst, InstFormatter, SymbolResolver, Syntax, Xlen};
+    ///
+    /// let symbols: &[(u64, &str)] = &[(0x1000, "main"), (0x1100, "exit")];
+    /// let (inst, _) = Inst::decode(0xff5ff0ef, Xlen::Rv32).unwrap();
+    /// assert_eq!(format!("{inst}"), "jal ra, -12");
+    ///
+    /// let formatter = InstFormatter::new();
+    /// let text = formatter.display_at(&inst, 0x1014, Xlen::Rv32, Some(&symbols)).to_string();
+    /// assert_eq!(text, "jal ra, 0x1008 <main+0x8>");
+    ///
+    /// let formatter = InstFormatter::new().with_syntax(Syntax::Gnu);
+    /// let text = formatter.display_at(&inst, 0x1014, Xlen::Rv32, None).to_string();
+    /// assert_eq!(text, "jal\t1008");
+    /// ```
+    pub fn display_at<'a>(
+        &'a self,
+        inst: &'a Inst,
+        pc: u64,
+        xlen: Xlen,
+        symbols: Option<&'a dyn SymbolResolver>,
+    ) -> FormattedInst<'a> {
+        FormattedInst {
+            formatter: self,
+            inst,
+            location: Some(Location { pc, xlen, symbols }),
+        }
+    }
+}
+
+impl Default for InstFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Looks up the symbols of addresses for [`InstFormatter::display_at`].
+pub trait SymbolResolver {
+    /// Returns the name of the symbol that contains `address`,
+    /// and the offset of `address` from the start of that symbol.
+    fn resolve(&self, address: u64) -> Option<(&str, u64)>;
+}
+
+/// A list of `(address, name)` symbols sorted by address,
+/// where each symbol extends up to the next one.
+impl SymbolResolver for &[(u64, &str)] {
+    fn resolve(&self, address: u64) -> Option<(&str, u64)> {
+        let index = self.partition_point(|&(start, _)| start <= address).checked_sub(1)?;
+        let (start, name) = self[index];
+        Some((name, address - start))
+    }
+}
+
+/// An instruction together with the [`InstFormatter`] to display it with,
+/// returned by [`InstFormatter::display`] and [`InstFormatter::display_at`].
+#[derive(Debug, Clone, Copy)]
+pub struct FormattedInst<'a> {
+    formatter: &'a InstFormatter,
+    inst: &'a Inst,
+    location: Option<Location<'a>>,
+}
+
+impl Display for FormattedInst<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inst.fmt_with(&mut Printer::new(f, self.formatter, self.location))
+    }
+}
+
+/// The address of an instruction, used to print absolute branch targets.
+#[derive(Clone, Copy)]
+pub(crate) struct Location<'a> {
+    pc: u64,
+    xlen: Xlen,
+    symbols: Option<&'a dyn SymbolResolver>,
+}
+
+impl Debug for Location<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Location")
+            .field("pc", &self.pc)
+            .field("xlen", &self.xlen)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Writes the mnemonic and operands of an instruction as configured by an [`InstFormatter`].
+pub(crate) struct Printer<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    options: &'a InstFormatter,
+    location: Option<Location<'a>>,
+}
+
+impl<'a, 'b> Printer<'a, 'b> {
+    pub(crate) fn new(
+        f: &'a mut fmt::Formatter<'b>,
+        options: &'a InstFormatter,
+        location: Option<Location<'a>>,
+    ) -> Self {
+        Self {
+            f,
+            options,
+            location,
+        }
+    }
+
+    /// Whether pseudo-instructions are used.
+    pub(crate) fn pseudo(&self) -> bool {
+        self.options.pseudo_instructions
+    }
+
+    /// Whether the aliases of the GNU and LLVM disassemblers are used on top of the
+    /// pseudo-instructions of the default syntax, like `seqz`, `jr` and `fmv.s`.
+    pub(crate) fn aliases(&self) -> bool {
+        self.options.pseudo_instructions && self.options.syntax != Syntax::Default
+    }
+
+    /// The name of `csr`, which the RV32-only CSRs don't have at a location on RV64.
+    /// Without a location the XLEN isn't known, so they are printed as numbers, which
+    /// assemble on both RV32 and RV64.
+    pub(crate) fn csr_name(&self, csr: Csr) -> Option<&'static str> {
+        match self.location {
+            Some(location) => csr.name_for(location.xlen),
+            None => csr.name().filter(|_| !csr.is_rv32_only()),
+        }
+    }
+
+    /// Writes `mnemonic` followed by `operands`.
+    pub(crate) fn inst(
+        &mut self,
+        mnemonic: impl Display,
+        operands: &[&dyn Operand],
+    ) -> fmt::Result {
+        write!(self.f, "{mnemonic}")?;
+        for (i, operand) in operands.iter().enumerate() {
+            if i == 0 {
+                self.f.write_str(match self.options.syntax {
+                    Syntax::Default => " ",
+                    Syntax::Gnu | Syntax::Llvm => "\t",
+                })?;
+            } else {
+                self.separator()?;
+            }
+            operand.print(self)?;
+        }
+        Ok(())
+    }
+
+    /// Writes an instruction with the rounding mode `rm` as its last operand,
+    /// which is left out for the dynamic rounding mode with pseudo-instructions.
+    pub(crate) fn inst_rm(
+        &mut self,
+        mnemonic: &str,
+        operands: &[&dyn Operand],
+        rm: RoundingMode,
+    ) -> fmt::Result {
+        self.inst(mnemonic, operands)?;
+        if self.pseudo() && matches!(rm, RoundingMode::Dynamic) {
+            return Ok(());
+        }
+        self.separator()?;
+        rm.print(self)
+    }
+
+    /// Writes an exact conversion like `fcvt.s.h`, where the assemblers default to `rne`
+    /// instead of `dyn`, so `rne` is dropped with pseudo-instructions instead.
+    pub(crate) fn inst_exact_rm(
+        &mut self,
+        mnemonic: &str,
+        operands: &[&dyn Operand],
+        rm: RoundingMode,
+    ) -> fmt::Result {
+        self.inst(mnemonic, operands)?;
+        if self.pseudo() && rm == RoundingMode::RoundToNearestTiesToEven {
+            return Ok(());
+        }
+        self.separator()?;
+        rm.print(self)
+    }
+
+    /// Writes a vector instruction, with `v0.t` as its last operand if it is `masked`.
+    pub(crate) fn inst_masked(
+        &mut self,
+        mnemonic: impl Display,
+        operands: &[&dyn Operand],
+        masked: bool,
+    ) -> fmt::Result {
+        self.inst(mnemonic, operands)?;
+        if !masked {
+            return Ok(());
+        }
+        self.separator()?;
+        self.f.write_str("v0.t")
+    }
+
+    /// Writes the address of the PC-relative `offset` as a comment, if the location is known.
+    pub(crate) fn address_comment(&mut self, offset: i64) -> fmt::Result {
+        if self.location.is_none() {
+            return Ok(());
+        }
+        self.f.write_str(" # ")?;
+        Target(offset).print(self)
+    }
+
+    /// Writes the separator between two operands.
+    pub(crate) fn separator(&mut self) -> fmt::Result {
+        match self.options.syntax {
+            Syntax::Gnu => self.f.write_str(","),
+            Syntax::Default | Syntax::Llvm => self.f.write_str(", "),
+        }
+    }
+
+    fn imm(&mut self, value: i64) -> fmt::Result {
+        match value {
+            _ if !self.options.hex_immediates => write!(self.f, "{value}"),
+            ..0 => write!(self.f, "-{:#x}", value.unsigned_abs()),
+            _ => write!(self.f, "{value:#x}"),
+        }
+    }
+
+    /// Writes `text` as it is.
+    pub(crate) fn text(&mut self, text: impl Display) -> fmt::Result {
+        write!(self.f, "{text}")
+    }
+}
+
+/// An operand of an instruction, written by a [`Printer`].
+pub(crate) trait Operand {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result;
+}
+
+impl Operand for Reg {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result {
+        if p.options.abi_names {
+            write!(p.f, "{self}")
+        } else {
+            write!(p.f, "x{}", self.0)
+        }
+    }
+}
+
+impl Operand for FReg {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result {
+        if p.options.abi_names {
+            write!(p.f, "{self}")
+        } else {
+            write!(p.f, "f{}", self.0)
+        }
+    }
+}
+
+impl Operand for VReg {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result {
+        write!(p.f, "{self}")
+    }
+}
+
+impl Operand for i32 {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result {
+        p.imm((*self).into())
+    }
+}
+
+impl Operand for u32 {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result {
+        p.imm((*self).into())
+    }
+}
+
+/// Operands like `rtz` that are always written the same way.
+impl Operand for &str {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result {
+        p.f.write_str(self)
+    }
+}
+
+impl Operand for RoundingMode {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result {
+        write!(p.f, "{self}")
+    }
+}
+
+impl Operand for FliConstant {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result {
+        p.f.write_str(self.name())
+    }
+}
+
+/// CSRs without a name are written in hex, except for LLVM syntax, which uses decimal
+/// unless hex immediates are enabled.
+impl Operand for Csr {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result {
+        match p.csr_name(*self) {
+            Some(name) => p.f.write_str(name),
+            None if p.options.syntax == Syntax::Llvm && !p.options.hex_immediates => {
+                write!(p.f, "{}", self.0)
+            }
+            None => write!(p.f, "{:#x}", self.0),
+        }
+    }
+}
+
+/// The shifted immediate of `lui` and `auipc`, which GNU syntax always writes in hex.
+pub(crate) struct UpperImm(pub(crate) u32);
+
+impl Operand for UpperImm {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result {
+        if p.options.syntax == Syntax::Gnu {
+            write!(p.f, "{:#x}", self.0)
+        } else {
+            p.imm(self.0.into())
+        }
+    }
+}
+
+/// A memory operand with an offset, like `8(sp)`.
+pub(crate) struct Mem(pub(crate) i32, pub(crate) Reg);
+
+impl Operand for Mem {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result {
+        self.0.print(p)?;
+        Addr(self.1).print(p)
+    }
+}
+
+/// A memory operand without an offset, like `(a0)`.
+pub(crate) struct Addr(pub(crate) Reg);
+
+impl Operand for Addr {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result {
+        p.f.write_str("(")?;
+        self.0.print(p)?;
+        p.f.write_str(")")
+    }
+}
+
+/// The predecessor and successor sets of a `fence`,
+/// which the default syntax separates without a space.
+pub(crate) struct FenceSets(pub(crate) FenceSet, pub(crate) FenceSet);
+
+impl Operand for FenceSets {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result {
+        write!(p.f, "{}", self.0)?;
+        match p.options.syntax {
+            Syntax::Default => p.f.write_str(",")?,
+            Syntax::Gnu | Syntax::Llvm => p.separator()?,
+        }
+        write!(p.f, "{}", self.1)
+    }
+}
+
+/// A PC-relative target, written as the offset or, with a [`Location`], as the absolute address
+/// and its symbol.
+pub(crate) struct Target(pub(crate) i64);
+
+impl Operand for Target {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result {
+        let Some(location) = p.location else {
+            return p.imm(self.0);
+        };
+        let address = location.xlen.wrap_address(location.pc.wrapping_add_signed(self.0));
+        if p.options.syntax == Syntax::Gnu {
+            // GNU writes targets without the prefix
+            write!(p.f, "{address:x}")?;
+        } else {
+            write!(p.f, "{address:#x}")?;
+        }
+        match location.symbols.and_then(|symbols| symbols.resolve(address)) {
+            Some((name, 0)) => write!(p.f, " <{name}>"),
+            Some((name, offset)) => write!(p.f, " <{name}+{offset:#x}>"),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,824 @@
+//! ISA configurations, which select the base ISA and the enabled extensions.
+
+use core::fmt::{self, Display};
+use core::ops::Range;
+use core::str::FromStr;
+
+use crate::{Inst, RegisterFile, Xlen};
+
+/// A standard extension that instructions can belong to.
+///
+/// Extensions that only group other extensions, like [`Extension::A`] and [`Extension::B`],
+/// enable all of their parts when added to an [`IsaConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Extension {
+    /// Base integer instructions (RV32I, RV64I and RV32E, RV64E)
+    I,
+    /// Integer multiplication and division
+    M,
+    /// Atomic instructions, consisting of [`Extension::Zalrsc`] and [`Extension::Zaamo`]
+    A,
+    /// Single-precision floating-point
+    F,
+    /// Double-precision floating-point
+    D,
+    /// Quad-precision floating-point
+    Q,
+    /// Compressed instructions
+    C,
+    /// Bit-manipulation, consisting of [`Extension::Zba`], [`Extension::Zbb`]
+    /// and [`Extension::Zbs`]
+    B,
+    /// Vector operations
+    V,
+    /// Hypervisor
+    H,
+    /// Control and status register instructions
+    Zicsr,
+    /// Instruction-fetch fence (`fence.i`)
+    Zifencei,
+    /// Pause hint
+    Zihintpause,
+    /// Integer conditional operations
+    Zicond,
+    /// Cache-block management
+    Zicbom,
+    /// Cache-block zero
+    Zicboz,
+    /// Cache-block prefetch hints
+    Zicbop,
+    /// Load-reserved and store-conditional
+    Zalrsc,
+    /// Atomic memory operations
+    Zaamo,
+    /// Atomic compare-and-swap
+    Zacas,
+    /// Byte and halfword atomic memory operations
+    Zabha,
+    /// Address generation
+    Zba,
+    /// Basic bit-manipulation
+    Zbb,
+    /// Carry-less multiplication
+    Zbc,
+    /// Single-bit instructions
+    Zbs,
+    /// Half-precision floating-point
+    Zfh,
+    /// Minimal half-precision floating-point (loads, stores, moves and conversions)
+    Zfhmin,
+    /// Additional floating-point instructions
+    Zfa,
+}
+
+/// The base integer ISA of an [`IsaConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Base {
+    /// The integer ISA with 32 registers, `x0` to `x31`.
+    I,
+    /// The embedded integer ISA with only 16 registers, `x0` to `x15`.
+    E,
+}
+
+/// The ISA of a hart: the register size, the base ISA and the enabled extensions.
+///
+/// It's usually parsed from a `-march` string like `rv32imac_zicsr_zifencei`.
+/// Converting an [`Xlen`] into an `IsaConfig` enables all extensions, which is what the decode
+/// functions do when they are passed an [`Xlen`].
+///
+/// # Examples
+///
+/// ```rust
+/// use rv_asm::{Extension, Inst, IsaConfig, Xlen};
+///
+/// let isa: IsaConfig = "rv32imc".parse().unwrap();
+/// assert_eq!(isa.xlen(), Xlen::Rv32);
+/// assert!(isa.has(Extension::C));
+/// assert!(!isa.has(Extension::A));
+///
+/// // amoadd.w a0, a1, (a2)
+/// assert!(Inst::decode(0x00b6252f, isa).is_err());
+/// assert!(Inst::decode(0x00b6252f, Xlen::Rv32).is_ok());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IsaConfig {
+    xlen: Xlen,
+    base: Base,
+    extensions: u64,
+}
+
+/// The error used for `-march` strings that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IsaParseError {
+    /// The byte range of the input that caused the error.
+    pub span: Range<usize>,
+    /// A description of what was wrong with the input.
+    pub message: &'static str,
+}
+
+impl Extension {
+    /// All extensions.
+    pub const ALL: &[Extension] = &[
+        Extension::I,
+        Extension::M,
+        Extension::A,
+        Extension::F,
+        Extension::D,
+        Extension::Q,
+        Extension::C,
+        Extension::B,
+        Extension::V,
+        Extension::H,
+        Extension::Zicsr,
+        Extension::Zifencei,
+        Extension::Zihintpause,
+        Extension::Zicond,
+        Extension::Zicbom,
+        Extension::Zicboz,
+        Extension::Zicbop,
+        Extension::Zalrsc,
+        Extension::Zaamo,
+        Extension::Zacas,
+        Extension::Zabha,
+        Extension::Zba,
+        Extension::Zbb,
+        Extension::Zbc,
+        Extension::Zbs,
+        Extension::Zfh,
+        Extension::Zfhmin,
+        Extension::Zfa,
+    ];
+
+    /// The name of the extension as used in `-march` strings, like `m` or `zicsr`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Extension::I => "i",
+            Extension::M => "m",
+            Extension::A => "a",
+            Extension::F => "f",
+            Extension::D => "d",
+            Extension::Q => "q",
+            Extension::C => "c",
+            Extension::B => "b",
+            Extension::V => "v",
+            Extension::H => "h",
+            Extension::Zicsr => "zicsr",
+            Extension::Zifencei => "zifencei",
+            Extension::Zihintpause => "zihintpause",
+            Extension::Zicond => "zicond",
+            Extension::Zicbom => "zicbom",
+            Extension::Zicboz => "zicboz",
+            Extension::Zicbop => "zicbop",
+            Extension::Zalrsc => "zalrsc",
+            Extension::Zaamo => "zaamo",
+            Extension::Zacas => "zacas",
+            Extension::Zabha => "zabha",
+            Extension::Zba => "zba",
+            Extension::Zbb => "zbb",
+            Extension::Zbc => "zbc",
+            Extension::Zbs => "zbs",
+            Extension::Zfh => "zfh",
+            Extension::Zfhmin => "zfhmin",
+            Extension::Zfa => "zfa",
+        }
+    }
+
+    /// The extensions that are implied by this one.
+    fn implies(self) -> &'static [Extension] {
+        match self {
+            Extension::A => &[Extension::Zalrsc, Extension::Zaamo],
+            Extension::B => &[Extension::Zba, Extension::Zbb, Extension::Zbs],
+            Extension::F | Extension::H => &[Extension::Zicsr],
+            Extension::D => &[Extension::F],
+            Extension::Q => &[Extension::D],
+            Extension::V => &[Extension::D],
+            Extension::Zfh => &[Extension::Zfhmin],
+            Extension::Zfhmin | Extension::Zfa => &[Extension::F],
+            Extension::Zacas | Extension::Zabha => &[Extension::Zaamo],
+            _ => &[],
+        }
+    }
+
+    const fn bit(self) -> u64 {
+        1 << self as u32
+    }
+}
+
+// Every extension needs its own bit in `IsaConfig::extensions`.
+const _: () = assert!(Extension::ALL.len() <= u64::BITS as usize);
+
+/// The bits of all extensions, so that creating the default ISA for decoding is cheap.
+const ALL_EXTENSIONS: u64 = {
+    let mut bits = 0;
+    let mut i = 0;
+    while i < Extension::ALL.len() {
+        bits |= Extension::ALL[i].bit();
+        i += 1;
+    }
+    bits
+};
+
+impl Display for Extension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl IsaConfig {
+    /// An ISA with the base integer instructions and all extensions supported by this crate.
+    pub fn new(xlen: Xlen) -> Self {
+        Self {
+            xlen,
+            base: Base::I,
+            extensions: ALL_EXTENSIONS,
+        }
+    }
+
+    /// An ISA with only the base integer instructions.
+    pub fn minimal(xlen: Xlen) -> Self {
+        Self {
+            xlen,
+            base: Base::I,
+            extensions: Extension::I.bit(),
+        }
+    }
+
+    /// Parses a `-march` string like `rv64gc` or `rv32imac_zicsr_zifencei`.
+    ///
+    /// The base is one of `i`, `e` or `g`, where `g` is short for `imafd_zicsr_zifencei`.
+    /// It's followed by the single-letter extensions and then the multi-letter extensions,
+    /// separated by underscores. Version numbers like `2p0` are ignored, and so are unknown
+    /// multi-letter extensions, as this crate has no instructions to decode for them.
+    pub fn parse(march: &str) -> Result<Self, IsaParseError> {
+        let error = |span: Range<usize>, message| Err(IsaParseError { span, message });
+
+        let xlen = match march.get(..4) {
+            Some("rv32") => Xlen::Rv32,
+            Some("rv64") => Xlen::Rv64,
+            _ => return error(0..march.len().min(4), "expected `rv32` or `rv64`"),
+        };
+        let mut isa = Self::minimal(xlen);
+        match march.get(4..5) {
+            Some("i") => {}
+            Some("e") => isa.base = Base::E,
+            Some("g") => {
+                for ext in [
+                    Extension::M,
+                    Extension::A,
+                    Extension::F,
+                    Extension::D,
+                    Extension::Zicsr,
+                    Extension::Zifencei,
+                ] {
+                    isa = isa.with(ext);
+                }
+            }
+            _ => return error(4..march.len().min(5), "expected base ISA `i`, `e` or `g`"),
+        }
+
+        let mut start = 5;
+        for part in march[5..].split('_') {
+            let span = start..start + part.len();
+            start = span.end + 1;
+            if part.is_empty() {
+                // there may be no single-letter extensions after the base
+                if span.start == 5 {
+                    continue;
+                }
+                return error(span, "empty extension name");
+            }
+
+            if part.starts_with(['z', 's', 'x']) {
+                let name = strip_version(part);
+                if let Some(ext) = Extension::ALL
+                    .iter()
+                    .find(|ext| ext.name().len() > 1 && ext.name() == name)
+                {
+                    isa = isa.with(*ext);
+                }
+                continue;
+            }
+
+            // the base ISA can have a version as well
+            let mut i = if span.start == 5 { version_len(part) } else { 0 };
+            while let Some(c) = part[i..].chars().next() {
+                match Extension::ALL
+                    .iter()
+                    .find(|ext| ext.name().len() == 1 && ext.name().starts_with(c))
+                {
+                    Some(ext) if *ext != Extension::I => isa = isa.with(*ext),
+                    _ => {
+                        let pos = span.start + i;
+                        return error(pos..pos + c.len_utf8(), "unknown extension");
+                    }
+                }
+                i += c.len_utf8();
+                i += version_len(&part[i..]);
+            }
+        }
+        Ok(isa)
+    }
+
+    /// The register size.
+    pub fn xlen(&self) -> Xlen {
+        self.xlen
+    }
+
+    /// The base integer ISA.
+    pub fn base(&self) -> Base {
+        self.base
+    }
+
+    /// Returns this ISA with the register size changed to `xlen`.
+    pub fn with_xlen(mut self, xlen: Xlen) -> Self {
+        self.xlen = xlen;
+        self
+    }
+
+    /// Returns this ISA with the base integer ISA changed to `base`.
+    pub fn with_base(mut self, base: Base) -> Self {
+        self.base = base;
+        self
+    }
+
+    /// Whether the extension is enabled.
+    pub fn has(&self, ext: Extension) -> bool {
+        self.extensions & ext.bit() != 0
+    }
+
+    /// Returns this ISA with the extension and the extensions it implies enabled.
+    pub fn with(mut self, ext: Extension) -> Self {
+        self.extensions |= ext.bit();
+        for implied in ext.implies() {
+            self = self.with(*implied);
+        }
+        self
+    }
+
+    /// Returns this ISA with the extension and all extensions that imply it disabled.
+    ///
+    /// [`Extension::I`] can't be disabled.
+    pub fn without(mut self, ext: Extension) -> Self {
+        if ext == Extension::I {
+            return self;
+        }
+        for other in Extension::ALL {
+            if Self::minimal(self.xlen).with(*other).has(ext) {
+                self.extensions &= !other.bit();
+            }
+        }
+        self
+    }
+
+    /// Whether the instruction is available in this ISA.
+    ///
+    /// This checks that the required extensions are enabled and that all integer registers
+    /// exist in the base ISA. It does not check whether the instruction is available for the XLEN.
+    pub fn supports(&self, inst: &Inst) -> bool {
+        self.missing_extension(inst).is_none() && self.has_registers_for(inst)
+    }
+
+    /// The first extension that the instruction needs, but that is not enabled.
+    pub(crate) fn missing_extension(&self, inst: &Inst) -> Option<Extension> {
+        let also_needs = match inst {
+            Inst::FcvtDH { .. } | Inst::FcvtHD { .. } => Extension::D,
+            Inst::FcvtHQ { .. } | Inst::FcvtQH { .. } => Extension::Zfhmin,
+            Inst::AmocasB { .. } | Inst::AmocasH { .. } => Extension::Zacas,
+            Inst::FliD { .. }
+            | Inst::FminmD { .. }
+            | Inst::FmaxmD { .. }
+            | Inst::FroundD { .. }
+            | Inst::FroundnxD { .. }
+            | Inst::FcvtmodWD { .. }
+            | Inst::FltqD { .. }
+            | Inst::FleqD { .. }
+            | Inst::FmvhXD { .. }
+            | Inst::FmvpDX { .. } => Extension::D,
+            Inst::FliH { .. }
+            | Inst::FminmH { .. }
+            | Inst::FmaxmH { .. }
+            | Inst::FroundH { .. }
+            | Inst::FroundnxH { .. }
+            | Inst::FltqH { .. }
+            | Inst::FleqH { .. } => Extension::Zfh,
+            _ => Extension::I,
+        };
+        [inst.extension(), also_needs]
+            .into_iter()
+            .find(|ext| !self.has(*ext))
+    }
+
+    pub(crate) fn has_registers_for(&self, inst: &Inst) -> bool {
+        match self.base {
+            Base::I => true,
+            Base::E => {
+                let mut all_exist = true;
+                inst.for_each_register(|_, file, reg| {
+                    all_exist &= file != RegisterFile::Int || reg < 16;
+                });
+                all_exist
+            }
+        }
+    }
+}
+
+/// The length of the version like `2p0` at the start of `s`.
+fn version_len(s: &str) -> usize {
+    let digits = |s: &str| s.bytes().take_while(u8::is_ascii_digit).count();
+    let major = digits(s);
+    if major == 0 {
+        return 0;
+    }
+    match s[major..].strip_prefix('p').map(digits) {
+        Some(minor) if minor > 0 => major + 1 + minor,
+        _ => major,
+    }
+}
+
+/// Strips the version like `1p0` from the end of a multi-letter extension name.
+fn strip_version(name: &str) -> &str {
+    let without_minor = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    if without_minor.len() == name.len() {
+        return name;
+    }
+    match without_minor.strip_suffix('p') {
+        Some(major) if major.ends_with(|c: char| c.is_ascii_digit()) => {
+            major.trim_end_matches(|c: char| c.is_ascii_digit())
+        }
+        _ => without_minor,
+    }
+}
+
+impl From<Xlen> for IsaConfig {
+    fn from(xlen: Xlen) -> Self {
+        Self::new(xlen)
+    }
+}
+
+impl FromStr for IsaConfig {
+    type Err = IsaParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Display for IsaParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to parse ISA string at {}..{}: {}",
+            self.span.start, self.span.end, self.message
+        )
+    }
+}
+
+impl core::error::Error for IsaParseError {}
+
+impl Inst {
+    /// The extension that this instruction belongs to.
+    ///
+    /// The privileged instructions that are not part of the hypervisor extension are reported
+    /// as [`Extension::I`]. Instructions that are shared by several extensions, like the
+    /// Zfhmin conversions between half and double precision, report their main extension.
+    pub fn extension(&self) -> Extension {
+        match self {
+            Inst::Lui { .. }
+            | Inst::Auipc { .. }
+            | Inst::Jal { .. }
+            | Inst::Jalr { .. }
+            | Inst::Beq { .. }
+            | Inst::Bne { .. }
+            | Inst::Blt { .. }
+            | Inst::Bge { .. }
+            | Inst::Bltu { .. }
+            | Inst::Bgeu { .. }
+            | Inst::Lb { .. }
+            | Inst::Lbu { .. }
+            | Inst::Lh { .. }
+            | Inst::Lhu { .. }
+            | Inst::Lw { .. }
+            | Inst::Lwu { .. }
+            | Inst::Ld { .. }
+            | Inst::Sb { .. }
+            | Inst::Sh { .. }
+            | Inst::Sw { .. }
+            | Inst::Sd { .. }
+            | Inst::Addi { .. }
+            | Inst::AddiW { .. }
+            | Inst::Slti { .. }
+            | Inst::Sltiu { .. }
+            | Inst::Xori { .. }
+            | Inst::Ori { .. }
+            | Inst::Andi { .. }
+            | Inst::Slli { .. }
+            | Inst::SlliW { .. }
+            | Inst::Srli { .. }
+            | Inst::SrliW { .. }
+            | Inst::Srai { .. }
+            | Inst::SraiW { .. }
+            | Inst::Add { .. }
+            | Inst::AddW { .. }
+            | Inst::Sub { .. }
+            | Inst::SubW { .. }
+            | Inst::Sll { .. }
+            | Inst::SllW { .. }
+            | Inst::Slt { .. }
+            | Inst::Sltu { .. }
+            | Inst::Xor { .. }
+            | Inst::Srl { .. }
+            | Inst::SrlW { .. }
+            | Inst::Sra { .. }
+            | Inst::SraW { .. }
+            | Inst::Or { .. }
+            | Inst::And { .. }
+            | Inst::Fence { .. } => Extension::I,
+            Inst::FenceI => Extension::Zifencei,
+            Inst::Ecall
+            | Inst::Ebreak => Extension::I,
+            Inst::Mul { .. }
+            | Inst::MulW { .. }
+            | Inst::Mulh { .. }
+            | Inst::Mulhsu { .. }
+            | Inst::Mulhu { .. }
+            | Inst::Div { .. }
+            | Inst::DivW { .. }
+            | Inst::Divu { .. }
+            | Inst::DivuW { .. }
+            | Inst::Rem { .. }
+            | Inst::RemW { .. }
+            | Inst::Remu { .. }
+            | Inst::RemuW { .. } => Extension::M,
+            Inst::LrW { .. }
+            | Inst::ScW { .. } => Extension::Zalrsc,
+            Inst::AmoW { .. } => Extension::Zaamo,
+            Inst::LrD { .. }
+            | Inst::ScD { .. } => Extension::Zalrsc,
+            Inst::AmoD { .. } => Extension::Zaamo,
+            Inst::AmoB { .. }
+            | Inst::AmoH { .. }
+            | Inst::AmocasB { .. }
+            | Inst::AmocasH { .. } => Extension::Zabha,
+            Inst::AmocasW { .. }
+            | Inst::AmocasD { .. }
+            | Inst::AmocasQ { .. } => Extension::Zacas,
+            Inst::Csrrw { .. }
+            | Inst::Csrrs { .. }
+            | Inst::Csrrc { .. }
+            | Inst::Csrrwi { .. }
+            | Inst::Csrrsi { .. }
+            | Inst::Csrrci { .. } => Extension::Zicsr,
+            Inst::Mret
+            | Inst::Sret
+            | Inst::Wfi
+            | Inst::SfenceVma { .. } => Extension::I,
+            Inst::HfenceVvma { .. }
+            | Inst::HfenceGvma { .. } => Extension::H,
+            Inst::Flw { .. }
+            | Inst::Fsw { .. }
+            | Inst::FmaddS { .. }
+            | Inst::FmsubS { .. }
+            | Inst::FnmsubS { .. }
+            | Inst::FnmaddS { .. }
+            | Inst::FaddS { .. }
+            | Inst::FsubS { .. }
+            | Inst::FmulS { .. }
+            | Inst::FdivS { .. }
+            | Inst::FsqrtS { .. }
+            | Inst::FsgnjS { .. }
+            | Inst::FsgnjnS { .. }
+            | Inst::FsgnjxS { .. }
+            | Inst::FminS { .. }
+            | Inst::FmaxS { .. }
+            | Inst::FcvtWS { .. }
+            | Inst::FcvtWuS { .. }
+            | Inst::FmvXW { .. }
+            | Inst::FeqS { .. }
+            | Inst::FltS { .. }
+            | Inst::FleS { .. }
+            | Inst::FclassS { .. }
+            | Inst::FcvtSW { .. }
+            | Inst::FcvtSWu { .. }
+            | Inst::FmvWX { .. } => Extension::F,
+            Inst::Fld { .. }
+            | Inst::Fsd { .. }
+            | Inst::FmaddD { .. }
+            | Inst::FmsubD { .. }
+            | Inst::FnmsubD { .. }
+            | Inst::FnmaddD { .. }
+            | Inst::FaddD { .. }
+            | Inst::FsubD { .. }
+            | Inst::FmulD { .. }
+            | Inst::FdivD { .. }
+            | Inst::FsqrtD { .. }
+            | Inst::FsgnjD { .. }
+            | Inst::FsgnjnD { .. }
+            | Inst::FsgnjxD { .. }
+            | Inst::FminD { .. }
+            | Inst::FmaxD { .. }
+            | Inst::FcvtSD { .. }
+            | Inst::FcvtDS { .. }
+            | Inst::FeqD { .. }
+            | Inst::FltD { .. }
+            | Inst::FleD { .. }
+            | Inst::FclassD { .. }
+            | Inst::FcvtWD { .. }
+            | Inst::FcvtWuD { .. }
+            | Inst::FcvtDW { .. }
+            | Inst::FcvtDWu { .. } => Extension::D,
+            Inst::FcvtLS { .. }
+            | Inst::FcvtLuS { .. }
+            | Inst::FcvtSL { .. }
+            | Inst::FcvtSLu { .. } => Extension::F,
+            Inst::FcvtLD { .. }
+            | Inst::FcvtLuD { .. }
+            | Inst::FmvXD { .. }
+            | Inst::FcvtDL { .. }
+            | Inst::FcvtDLu { .. }
+            | Inst::FmvDX { .. } => Extension::D,
+            Inst::Flh { .. }
+            | Inst::Fsh { .. } => Extension::Zfhmin,
+            Inst::FmaddH { .. }
+            | Inst::FmsubH { .. }
+            | Inst::FnmsubH { .. }
+            | Inst::FnmaddH { .. }
+            | Inst::FaddH { .. }
+            | Inst::FsubH { .. }
+            | Inst::FmulH { .. }
+            | Inst::FdivH { .. }
+            | Inst::FsqrtH { .. }
+            | Inst::FsgnjH { .. }
+            | Inst::FsgnjnH { .. }
+            | Inst::FsgnjxH { .. }
+            | Inst::FminH { .. }
+            | Inst::FmaxH { .. } => Extension::Zfh,
+            Inst::FcvtSH { .. }
+            | Inst::FcvtHS { .. }
+            | Inst::FcvtDH { .. }
+            | Inst::FcvtHD { .. } => Extension::Zfhmin,
+            Inst::FeqH { .. }
+            | Inst::FltH { .. }
+            | Inst::FleH { .. }
+            | Inst::FclassH { .. }
+            | Inst::FcvtWH { .. }
+            | Inst::FcvtWuH { .. }
+            | Inst::FcvtHW { .. }
+            | Inst::FcvtHWu { .. } => Extension::Zfh,
+            Inst::FmvXH { .. }
+            | Inst::FmvHX { .. } => Extension::Zfhmin,
+            Inst::FcvtLH { .. }
+            | Inst::FcvtLuH { .. }
+            | Inst::FcvtHL { .. }
+            | Inst::FcvtHLu { .. } => Extension::Zfh,
+            Inst::FliS { .. }
+            | Inst::FminmS { .. }
+            | Inst::FmaxmS { .. }
+            | Inst::FroundS { .. }
+            | Inst::FroundnxS { .. }
+            | Inst::FltqS { .. }
+            | Inst::FleqS { .. }
+            | Inst::FliD { .. }
+            | Inst::FminmD { .. }
+            | Inst::FmaxmD { .. }
+            | Inst::FroundD { .. }
+            | Inst::FroundnxD { .. }
+            | Inst::FcvtmodWD { .. }
+            | Inst::FltqD { .. }
+            | Inst::FleqD { .. }
+            | Inst::FmvhXD { .. }
+            | Inst::FmvpDX { .. }
+            | Inst::FliH { .. }
+            | Inst::FminmH { .. }
+            | Inst::FmaxmH { .. }
+            | Inst::FroundH { .. }
+            | Inst::FroundnxH { .. }
+            | Inst::FltqH { .. }
+            | Inst::FleqH { .. } => Extension::Zfa,
+            Inst::Flq { .. }
+            | Inst::Fsq { .. }
+            | Inst::FmaddQ { .. }
+            | Inst::FmsubQ { .. }
+            | Inst::FnmsubQ { .. }
+            | Inst::FnmaddQ { .. }
+            | Inst::FaddQ { .. }
+            | Inst::FsubQ { .. }
+            | Inst::FmulQ { .. }
+            | Inst::FdivQ { .. }
+            | Inst::FsqrtQ { .. }
+            | Inst::FsgnjQ { .. }
+            | Inst::FsgnjnQ { .. }
+            | Inst::FsgnjxQ { .. }
+            | Inst::FminQ { .. }
+            | Inst::FmaxQ { .. }
+            | Inst::FcvtSQ { .. }
+            | Inst::FcvtQS { .. }
+            | Inst::FcvtDQ { .. }
+            | Inst::FcvtQD { .. }
+            | Inst::FcvtHQ { .. }
+            | Inst::FcvtQH { .. }
+            | Inst::FeqQ { .. }
+            | Inst::FltQ { .. }
+            | Inst::FleQ { .. }
+            | Inst::FclassQ { .. }
+            | Inst::FcvtWQ { .. }
+            | Inst::FcvtWuQ { .. }
+            | Inst::FcvtQW { .. }
+            | Inst::FcvtQWu { .. }
+            | Inst::FcvtLQ { .. }
+            | Inst::FcvtLuQ { .. }
+            | Inst::FcvtQL { .. }
+            | Inst::FcvtQLu { .. } => Extension::Q,
+            Inst::Sh1add { .. }
+            | Inst::Sh2add { .. }
+            | Inst::Sh3add { .. }
+            | Inst::AddUw { .. }
+            | Inst::Sh1addUw { .. }
+            | Inst::Sh2addUw { .. }
+            | Inst::Sh3addUw { .. }
+            | Inst::SlliUw { .. } => Extension::Zba,
+            Inst::Andn { .. }
+            | Inst::Orn { .. }
+            | Inst::Xnor { .. }
+            | Inst::Clz { .. }
+            | Inst::ClzW { .. }
+            | Inst::Ctz { .. }
+            | Inst::CtzW { .. }
+            | Inst::Cpop { .. }
+            | Inst::CpopW { .. }
+            | Inst::Max { .. }
+            | Inst::Maxu { .. }
+            | Inst::Min { .. }
+            | Inst::Minu { .. }
+            | Inst::SextB { .. }
+            | Inst::SextH { .. }
+            | Inst::ZextH { .. }
+            | Inst::Rol { .. }
+            | Inst::RolW { .. }
+            | Inst::Ror { .. }
+            | Inst::RorW { .. }
+            | Inst::Rori { .. }
+            | Inst::RoriW { .. }
+            | Inst::OrcB { .. }
+            | Inst::Rev8 { .. } => Extension::Zbb,
+            Inst::Clmul { .. }
+            | Inst::Clmulh { .. }
+            | Inst::Clmulr { .. } => Extension::Zbc,
+            Inst::Bclr { .. }
+            | Inst::Bclri { .. }
+            | Inst::Bext { .. }
+            | Inst::Bexti { .. }
+            | Inst::Binv { .. }
+            | Inst::Binvi { .. }
+            | Inst::Bset { .. }
+            | Inst::Bseti { .. } => Extension::Zbs,
+            Inst::CzeroEqz { .. } | Inst::CzeroNez { .. } => Extension::Zicond,
+            Inst::CboClean { .. } | Inst::CboFlush { .. } | Inst::CboInval { .. } => {
+                Extension::Zicbom
+            }
+            Inst::CboZero { .. } => Extension::Zicboz,
+            Inst::PrefetchI { .. } | Inst::PrefetchR { .. } | Inst::PrefetchW { .. } => {
+                Extension::Zicbop
+            }
+            Inst::Vsetvli { .. }
+            | Inst::Vsetivli { .. }
+            | Inst::Vsetvl { .. }
+            | Inst::Vle { .. }
+            | Inst::Vleff { .. }
+            | Inst::Vlm { .. }
+            | Inst::Vlr { .. }
+            | Inst::Vlse { .. }
+            | Inst::Vluxei { .. }
+            | Inst::Vloxei { .. }
+            | Inst::Vse { .. }
+            | Inst::Vsm { .. }
+            | Inst::Vsr { .. }
+            | Inst::Vsse { .. }
+            | Inst::Vsuxei { .. }
+            | Inst::Vsoxei { .. }
+            | Inst::Opivv { .. }
+            | Inst::Opivx { .. }
+            | Inst::Opivi { .. }
+            | Inst::Opmvv { .. }
+            | Inst::Opmvx { .. }
+            | Inst::Opfvv { .. }
+            | Inst::Opfvf { .. }
+            | Inst::VmvXS { .. }
+            | Inst::VmvSX { .. }
+            | Inst::VcpopM { .. }
+            | Inst::VfirstM { .. }
+            | Inst::Vzext { .. }
+            | Inst::Vsext { .. }
+            | Inst::VmsbfM { .. }
+            | Inst::VmsofM { .. }
+            | Inst::VmsifM { .. }
+            | Inst::ViotaM { .. }
+            | Inst::VidV { .. }
+            | Inst::VfmvFS { .. }
+            | Inst::VfmvSF { .. }
+            | Inst::Vfcvt { .. }
+            | Inst::VfUnary { .. }
+            | Inst::VmvNrR { .. } => Extension::V,
+        }
+    }
+}
@@ -0,0 +1,287 @@
+//! The classification of instructions and their control flow.
+
+use crate::{Extension, Inst, Reg, Xlen};
+
+/// The kind of an instruction, see [`Inst::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum InstKind {
+    /// Integer computation, like `add`, `lui` or `mul`
+    Alu,
+    /// Integer, floating-point or vector load
+    Load,
+    /// Integer, floating-point or vector store
+    Store,
+    /// Conditional branch
+    Branch,
+    /// Jump that is neither a call nor a return, like `j` or an indirect `jr`
+    Jump,
+    /// Jump that links to the return address register, see [`Inst::is_call`]
+    Call,
+    /// Jump back to the return address register, see [`Inst::is_return`]
+    Return,
+    /// Atomic memory operation, including `lr` and `sc`
+    Atomic,
+    /// CSR access
+    Csr,
+    /// Fences, cache-block management, prefetch hints, environment calls, trap returns and other
+    /// privileged instructions
+    System,
+    /// Floating-point computation, like `fadd.s`, `fsgnj.d` or `feq.h`
+    FpArith,
+    /// Floating-point conversion and move between integer and floating-point registers
+    FpConvert,
+    /// Vector computation or configuration
+    Vector,
+}
+
+/// Whether `reg` is a link register (`ra` or `t0`), which the return-address stack hints use.
+fn is_link(reg: Reg) -> bool {
+    reg == Reg::RA || reg == Reg::T0
+}
+
+impl Inst {
+    /// The kind of this instruction.
+    ///
+    /// `jal` and `jalr` are classified with the return-address stack hints of the
+    /// specification, see [`Self::is_call`] and [`Self::is_return`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use rv_asm::{Inst, InstKind, Xlen};
+    ///
+    /// let kind = |text| Inst::parse_asm(text, Xlen::Rv32).unwrap().kind();
+    /// assert_eq!(kind("addi a0, a0, 1"), InstKind::Alu);
+    /// assert_eq!(kind("jal ra, 16"), InstKind::Call);
+    /// assert_eq!(kind("jalr zero, 0(ra)"), InstKind::Return);
+    /// assert_eq!(kind("fcvt.w.s a0, fa0, rtz"), InstKind::FpConvert);
+    /// ```
+    pub fn kind(&self) -> InstKind {
+        match *self {
+            Inst::Jal { .. } | Inst::Jalr { .. } if self.is_call() => InstKind::Call,
+            Inst::Jalr { .. } if self.is_return() => InstKind::Return,
+            Inst::Jal { .. } | Inst::Jalr { .. } => InstKind::Jump,
+            Inst::Beq { .. }
+            | Inst::Bne { .. }
+            | Inst::Blt { .. }
+            | Inst::Bge { .. }
+            | Inst::Bltu { .. }
+            | Inst::Bgeu { .. } => InstKind::Branch,
+            Inst::Lb { .. }
+            | Inst::Lbu { .. }
+            | Inst::Lh { .. }
+            | Inst::Lhu { .. }
+            | Inst::Lw { .. }
+            | Inst::Lwu { .. }
+            | Inst::Ld { .. }
+            | Inst::Flw { .. }
+            | Inst::Fld { .. }
+            | Inst::Flh { .. }
+            | Inst::Flq { .. }
+            | Inst::Vle { .. }
+            | Inst::Vleff { .. }
+            | Inst::Vlm { .. }
+            | Inst::Vlr { .. }
+            | Inst::Vlse { .. }
+            | Inst::Vluxei { .. }
+            | Inst::Vloxei { .. } => InstKind::Load,
+            Inst::Sb { .. }
+            | Inst::Sh { .. }
+            | Inst::Sw { .. }
+            | Inst::Sd { .. }
+            | Inst::Fsw { .. }
+            | Inst::Fsd { .. }
+            | Inst::Fsh { .. }
+            | Inst::Fsq { .. }
+            | Inst::Vse { .. }
+            | Inst::Vsm { .. }
+            | Inst::Vsr { .. }
+            | Inst::Vsse { .. }
+            | Inst::Vsuxei { .. }
+            | Inst::Vsoxei { .. }
+            | Inst::CboZero { .. } => InstKind::Store,
+            Inst::LrW { .. }
+            | Inst::ScW { .. }
+            | Inst::AmoW { .. }
+            | Inst::LrD { .. }
+            | Inst::ScD { .. }
+            | Inst::AmoD { .. }
+            | Inst::AmoB { .. }
+            | Inst::AmoH { .. }
+            | Inst::AmocasB { .. }
+            | Inst::AmocasH { .. }
+            | Inst::AmocasW { .. }
+            | Inst::AmocasD { .. }
+            | Inst::AmocasQ { .. } => InstKind::Atomic,
+            Inst::Csrrw { .. }
+            | Inst::Csrrs { .. }
+            | Inst::Csrrc { .. }
+            | Inst::Csrrwi { .. }
+            | Inst::Csrrsi { .. }
+            | Inst::Csrrci { .. } => InstKind::Csr,
+            Inst::Fence { .. }
+            | Inst::FenceI
+            | Inst::Ecall
+            | Inst::Ebreak
+            | Inst::Mret
+            | Inst::Sret
+            | Inst::Wfi
+            | Inst::SfenceVma { .. }
+            | Inst::HfenceVvma { .. }
+            | Inst::HfenceGvma { .. }
+            | Inst::CboClean { .. }
+            | Inst::CboFlush { .. }
+            | Inst::CboInval { .. }
+            | Inst::PrefetchI { .. }
+            | Inst::PrefetchR { .. }
+            | Inst::PrefetchW { .. } => InstKind::System,
+            Inst::FcvtWS { .. }
+            | Inst::FcvtWuS { .. }
+            | Inst::FmvXW { .. }
+            | Inst::FcvtSW { .. }
+            | Inst::FcvtSWu { .. }
+            | Inst::FmvWX { .. }
+            | Inst::FcvtSD { .. }
+            | Inst::FcvtDS { .. }
+            | Inst::FcvtWD { .. }
+            | Inst::FcvtWuD { .. }
+            | Inst::FcvtDW { .. }
+            | Inst::FcvtDWu { .. }
+            | Inst::FcvtLS { .. }
+            | Inst::FcvtLuS { .. }
+            | Inst::FcvtSL { .. }
+            | Inst::FcvtSLu { .. }
+            | Inst::FcvtLD { .. }
+            | Inst::FcvtLuD { .. }
+            | Inst::FmvXD { .. }
+            | Inst::FcvtDL { .. }
+            | Inst::FcvtDLu { .. }
+            | Inst::FmvDX { .. }
+            | Inst::FcvtSH { .. }
+            | Inst::FcvtHS { .. }
+            | Inst::FcvtDH { .. }
+            | Inst::FcvtHD { .. }
+            | Inst::FcvtWH { .. }
+            | Inst::FcvtWuH { .. }
+            | Inst::FcvtHW { .. }
+            | Inst::FcvtHWu { .. }
+            | Inst::FmvXH { .. }
+            | Inst::FmvHX { .. }
+            | Inst::FcvtLH { .. }
+            | Inst::FcvtLuH { .. }
+            | Inst::FcvtHL { .. }
+            | Inst::FcvtHLu { .. }
+            | Inst::FcvtSQ { .. }
+            | Inst::FcvtQS { .. }
+            | Inst::FcvtDQ { .. }
+            | Inst::FcvtQD { .. }
+            | Inst::FcvtHQ { .. }
+            | Inst::FcvtQH { .. }
+            | Inst::FcvtWQ { .. }
+            | Inst::FcvtWuQ { .. }
+            | Inst::FcvtQW { .. }
+            | Inst::FcvtQWu { .. }
+            | Inst::FcvtLQ { .. }
+            | Inst::FcvtLuQ { .. }
+            | Inst::FcvtQL { .. }
+            | Inst::FcvtQLu { .. }
+            | Inst::FcvtmodWD { .. }
+            | Inst::FmvhXD { .. }
+            | Inst::FmvpDX { .. } => InstKind::FpConvert,
+            _ => match self.extension() {
+                Extension::F
+                | Extension::D
+                | Extension::Q
+                | Extension::Zfh
+                | Extension::Zfhmin
+                | Extension::Zfa => InstKind::FpArith,
+                Extension::V => InstKind::Vector,
+                _ => InstKind::Alu,
+            },
+        }
+    }
+
+    /// The target of a branch or direct jump at `pc`, which wraps around at the end of the
+    /// address space of `xlen`.
+    ///
+    /// Returns `None` for all other instructions, including the indirect jumps of `jalr`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rv_asm::{Inst, Xlen};
+    ///
+    /// let inst = Inst::parse_asm("beq a0, a1, -8", Xlen::Rv64).unwrap();
+    /// assert_eq!(inst.branch_target(0x1000, Xlen::Rv64), Some(0xff8));
+    /// assert_eq!(inst.branch_target(0, Xlen::Rv32), Some(0xffff_fff8));
+    /// ```
+    pub fn branch_target(&self, pc: u64, xlen: Xlen) -> Option<u64> {
+        match *self {
+            Inst::Jal { offset, .. }
+            | Inst::Beq { offset, .. }
+            | Inst::Bne { offset, .. }
+            | Inst::Blt { offset, .. }
+            | Inst::Bge { offset, .. }
+            | Inst::Bltu { offset, .. }
+            | Inst::Bgeu { offset, .. } => {
+                Some(xlen.wrap_address(pc.wrapping_add_signed(offset.as_i64())))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether this instruction ends a basic block: branches, jumps, calls, returns,
+    /// `mret` and `sret`.
+    ///
+    /// Instructions that trap, like `ecall`, continue with the next instruction after the trap
+    /// handler returns, so they are not terminators.
+    pub fn is_terminator(&self) -> bool {
+        matches!(
+            self,
+            Inst::Jal { .. }
+                | Inst::Jalr { .. }
+                | Inst::Beq { .. }
+                | Inst::Bne { .. }
+                | Inst::Blt { .. }
+                | Inst::Bge { .. }
+                | Inst::Bltu { .. }
+                | Inst::Bgeu { .. }
+                | Inst::Mret
+                | Inst::Sret
+        )
+    }
+
+    /// Whether this instruction is a call, a `jal` or `jalr` that writes `ra` or `t0`.
+    ///
+    /// This matches the return-address stack hints of the specification, where these push
+    /// the return address. A `jalr` between two different link registers, like
+    /// `jalr t0, 0(ra)`, is both a call and a return, which swaps coroutines.
+    pub fn is_call(&self) -> bool {
+        match *self {
+            Inst::Jal { dest, .. } | Inst::Jalr { dest, .. } => is_link(dest),
+            _ => false,
+        }
+    }
+
+    /// Whether this instruction is a return, a `jalr` that jumps to `ra` or `t0`
+    /// without writing the same register.
+    ///
+    /// This matches the return-address stack hints of the specification, where these pop
+    /// the return address. `jalr ra, 0(ra)` is only a call.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rv_asm::{Inst, Xlen};
+    ///
+    /// let inst = |text| Inst::parse_asm(text, Xlen::Rv32).unwrap();
+    /// assert!(inst("jalr zero, 0(ra)").is_return());
+    /// assert!(inst("jalr ra, 0(a0)").is_call());
+    /// assert!(inst("jalr t0, 0(ra)").is_call() && inst("jalr t0, 0(ra)").is_return());
+    /// assert!(!inst("jalr ra, 0(ra)").is_return());
+    /// ```
+    pub fn is_return(&self) -> bool {
+        match *self {
+            Inst::Jalr { dest, base, .. } => is_link(base) && dest != base,
+            _ => false,
+        }
+    }
+}
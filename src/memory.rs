@@ -0,0 +1,188 @@
+//! The memory accesses of loads, stores and atomics, and their ordering by fences.
+
+use crate::{AmoOp, AmoOrdering, Fence, FenceSet, Imm, Inst, Reg, Xlen};
+
+/// The memory access of an instruction, see [`Inst::memory_access`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct MemAccess {
+    /// The base register of the address.
+    pub base: Reg,
+    /// The offset that is added to the base register, zero for atomics.
+    pub offset: Imm,
+    /// The number of bytes that are accessed: 1, 2, 4, 8 or 16.
+    pub width: u8,
+    /// Whether a load sign-extends the value to XLEN bits.
+    pub signed: bool,
+    /// Whether the memory is read, written or both.
+    pub kind: MemAccessKind,
+    /// The ordering bits of atomics, [`AmoOrdering::Relaxed`] for other accesses.
+    pub ordering: AmoOrdering,
+}
+
+/// How an instruction accesses memory, see [`MemAccess`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemAccessKind {
+    /// A load
+    Read,
+    /// A store
+    Write,
+    /// A load-reserved (`lr`), which reads and registers a reservation
+    LoadReserved,
+    /// A store-conditional (`sc`), which only writes if the reservation is still valid
+    StoreConditional,
+    /// An atomic memory operation, which reads, applies the operation and writes
+    Amo(AmoOp),
+    /// An atomic compare-and-swap (`amocas`), which reads and only writes if the value is equal
+    CompareAndSwap,
+}
+
+impl MemAccess {
+    fn new(base: Reg, offset: Imm, width: u8, kind: MemAccessKind) -> Self {
+        Self {
+            base,
+            offset,
+            width,
+            signed: false,
+            kind,
+            ordering: AmoOrdering::Relaxed,
+        }
+    }
+
+    fn signed(self) -> Self {
+        Self { signed: true, ..self }
+    }
+
+    /// An atomic access, where the loaded values are sign-extended.
+    fn atomic(base: Reg, width: u8, kind: MemAccessKind, ordering: AmoOrdering) -> Self {
+        let access = Self::new(base, Imm::ZERO, width, kind);
+        Self {
+            signed: access.reads(),
+            ordering,
+            ..access
+        }
+    }
+
+    /// Whether the access reads memory.
+    pub fn reads(&self) -> bool {
+        !matches!(self.kind, MemAccessKind::Write | MemAccessKind::StoreConditional)
+    }
+
+    /// Whether the access writes memory.
+    ///
+    /// A store-conditional or compare-and-swap is counted as a write, even though it may not
+    /// write.
+    pub fn writes(&self) -> bool {
+        !matches!(self.kind, MemAccessKind::Read | MemAccessKind::LoadReserved)
+    }
+
+    /// The address of the access, when the base register has the value `base`,
+    /// which wraps around at the end of the address space of `xlen`.
+    pub fn address(&self, base: u64, xlen: Xlen) -> u64 {
+        xlen.wrap_address(base.wrapping_add(self.offset.as_u64()))
+    }
+}
+
+impl Inst {
+    /// The memory access of a load, store or atomic instruction.
+    ///
+    /// Returns `None` for all other instructions, including fences (see [`Fence::orders`]),
+    /// vector loads and stores, whose accesses depend on `vl` and `vtype`, and the cache-block
+    /// operations, whose block size is implementation-defined.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rv_asm::{AmoOp, AmoOrdering, Imm, Inst, MemAccessKind, Reg, Xlen};
+    ///
+    /// let lh = Inst::parse_asm("lh a0, -2(sp)", Xlen::Rv32).unwrap();
+    /// let access = lh.memory_access().unwrap();
+    /// assert_eq!((access.base, access.offset), (Reg::SP, Imm::new_i32(-2)));
+    /// assert_eq!((access.width, access.signed, access.kind), (2, true, MemAccessKind::Read));
+    /// assert_eq!(access.address(0x1000, Xlen::Rv32), 0xffe);
+    /// assert_eq!(access.address(0, Xlen::Rv32), 0xffff_fffe);
+    ///
+    /// let amo = Inst::parse_asm("amoadd.d.aqrl a0, a1, (a2)", Xlen::Rv64).unwrap();
+    /// let access = amo.memory_access().unwrap();
+    /// assert_eq!((access.base, access.width), (Reg::A2, 8));
+    /// assert_eq!(access.kind, MemAccessKind::Amo(AmoOp::Add));
+    /// assert_eq!(access.ordering, AmoOrdering::SeqCst);
+    /// ```
+    pub fn memory_access(&self) -> Option<MemAccess> {
+        use MemAccessKind::{Amo, CompareAndSwap, LoadReserved, Read, StoreConditional, Write};
+
+        let access = match *self {
+            Inst::Lb { offset, base, .. } => MemAccess::new(base, offset, 1, Read).signed(),
+            Inst::Lbu { offset, base, .. } => MemAccess::new(base, offset, 1, Read),
+            Inst::Lh { offset, base, .. } => MemAccess::new(base, offset, 2, Read).signed(),
+            Inst::Lhu { offset, base, .. } => MemAccess::new(base, offset, 2, Read),
+            Inst::Lw { offset, base, .. } => MemAccess::new(base, offset, 4, Read).signed(),
+            Inst::Lwu { offset, base, .. } => MemAccess::new(base, offset, 4, Read),
+            Inst::Ld { offset, base, .. } => MemAccess::new(base, offset, 8, Read).signed(),
+            Inst::Sb { offset, base, .. } => MemAccess::new(base, offset, 1, Write),
+            Inst::Sh { offset, base, .. } => MemAccess::new(base, offset, 2, Write),
+            Inst::Sw { offset, base, .. } => MemAccess::new(base, offset, 4, Write),
+            Inst::Sd { offset, base, .. } => MemAccess::new(base, offset, 8, Write),
+            Inst::Flh { offset, base, .. } => MemAccess::new(base, offset, 2, Read),
+            Inst::Flw { offset, base, .. } => MemAccess::new(base, offset, 4, Read),
+            Inst::Fld { offset, base, .. } => MemAccess::new(base, offset, 8, Read),
+            Inst::Flq { offset, base, .. } => MemAccess::new(base, offset, 16, Read),
+            Inst::Fsh { offset, base, .. } => MemAccess::new(base, offset, 2, Write),
+            Inst::Fsw { offset, base, .. } => MemAccess::new(base, offset, 4, Write),
+            Inst::Fsd { offset, base, .. } => MemAccess::new(base, offset, 8, Write),
+            Inst::Fsq { offset, base, .. } => MemAccess::new(base, offset, 16, Write),
+            Inst::LrW { order, addr, .. } => MemAccess::atomic(addr, 4, LoadReserved, order),
+            Inst::LrD { order, addr, .. } => MemAccess::atomic(addr, 8, LoadReserved, order),
+            Inst::ScW { order, addr, .. } => MemAccess::atomic(addr, 4, StoreConditional, order),
+            Inst::ScD { order, addr, .. } => MemAccess::atomic(addr, 8, StoreConditional, order),
+            Inst::AmoW { order, op, addr, .. } => MemAccess::atomic(addr, 4, Amo(op), order),
+            Inst::AmoD { order, op, addr, .. } => MemAccess::atomic(addr, 8, Amo(op), order),
+            Inst::AmoB { order, op, addr, .. } => MemAccess::atomic(addr, 1, Amo(op), order),
+            Inst::AmoH { order, op, addr, .. } => MemAccess::atomic(addr, 2, Amo(op), order),
+            Inst::AmocasB { order, addr, .. } => MemAccess::atomic(addr, 1, CompareAndSwap, order),
+            Inst::AmocasH { order, addr, .. } => MemAccess::atomic(addr, 2, CompareAndSwap, order),
+            Inst::AmocasW { order, addr, .. } => MemAccess::atomic(addr, 4, CompareAndSwap, order),
+            Inst::AmocasD { order, addr, .. } => MemAccess::atomic(addr, 8, CompareAndSwap, order),
+            Inst::AmocasQ { order, addr, .. } => MemAccess::atomic(addr, 16, CompareAndSwap, order),
+            _ => return None,
+        };
+        Some(access)
+    }
+}
+
+impl FenceSet {
+    /// Whether the set includes `access`, with `r` for reads and `w` for writes.
+    /// Atomics that read and write are included by either.
+    ///
+    /// All accesses are treated as main memory accesses, so `i` and `o` are ignored.
+    pub fn includes(&self, access: &MemAccess) -> bool {
+        (self.memory_read && access.reads()) || (self.memory_write && access.writes())
+    }
+}
+
+impl Fence {
+    /// Whether the fence orders `before`, which comes earlier in program order,
+    /// before `after`, which comes later.
+    ///
+    /// A `fence.tso` (see [`Fence::is_tso`]) doesn't order stores before loads.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rv_asm::{Inst, Xlen};
+    ///
+    /// let inst = |text| Inst::parse_asm(text, Xlen::Rv32).unwrap();
+    /// let access = |text| inst(text).memory_access().unwrap();
+    /// let (load, store) = (access("lw a0, 0(a1)"), access("sw a0, 0(a1)"));
+    ///
+    /// let Inst::Fence { fence } = inst("fence w, r") else { unreachable!() };
+    /// assert!(fence.orders(&store, &load));
+    /// assert!(!fence.orders(&load, &store));
+    ///
+    /// let Inst::Fence { fence } = inst("fence.tso") else { unreachable!() };
+    /// assert!(fence.orders(&load, &store));
+    /// assert!(!fence.orders(&store, &load));
+    /// ```
+    pub fn orders(&self, before: &MemAccess, after: &MemAccess) -> bool {
+        let store_load = !before.reads() && !after.writes();
+        self.pred.includes(before) && self.succ.includes(after) && !(self.is_tso() && store_load)
+    }
+}
@@ -0,0 +1,533 @@
+//! The registers that instructions read and write, including implicit operands.
+
+use core::fmt::{self, Display};
+use core::iter::FusedIterator;
+
+use crate::vector::Kind;
+use crate::{
+    Csr, Extension, FReg, Inst, OpfFunct6, OpiFunct6, OpmFunct6, Reg, RegisterFile, RoundingMode,
+    VReg, VfUnaryOp, VfcvtOp,
+};
+
+/// A register that an instruction reads or writes, see [`Inst::uses`] and [`Inst::defs`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Register {
+    /// An integer register
+    Int(Reg),
+    /// A floating-point register
+    Float(FReg),
+    /// A vector register, or the first register of a register group
+    Vector(VReg),
+    /// A CSR
+    Csr(Csr),
+}
+
+impl From<Reg> for Register {
+    fn from(reg: Reg) -> Self {
+        Self::Int(reg)
+    }
+}
+
+impl From<FReg> for Register {
+    fn from(reg: FReg) -> Self {
+        Self::Float(reg)
+    }
+}
+
+impl From<VReg> for Register {
+    fn from(reg: VReg) -> Self {
+        Self::Vector(reg)
+    }
+}
+
+impl From<Csr> for Register {
+    fn from(csr: Csr) -> Self {
+        Self::Csr(csr)
+    }
+}
+
+impl Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(reg) => write!(f, "{reg}"),
+            Self::Float(reg) => write!(f, "{reg}"),
+            Self::Vector(reg) => write!(f, "{reg}"),
+            Self::Csr(csr) => write!(f, "{csr}"),
+        }
+    }
+}
+
+/// The most registers that an instruction reads, like a masked `vfmacc.vv`, which reads
+/// `vd`, `vs1`, `vs2`, `v0`, `vl`, `vtype` and `frm`.
+const CAPACITY: usize = 8;
+
+/// An iterator over the registers that an instruction reads or writes,
+/// returned by [`Inst::uses`] and [`Inst::defs`].
+///
+/// Every register is only returned once, in the order of the operands.
+#[derive(Debug, Clone)]
+pub struct Registers {
+    registers: [Register; CAPACITY],
+    start: usize,
+    end: usize,
+}
+
+impl Registers {
+    fn new() -> Self {
+        Self {
+            registers: [Register::Int(Reg::ZERO); CAPACITY],
+            start: 0,
+            end: 0,
+        }
+    }
+
+    fn push(&mut self, register: impl Into<Register>) {
+        let register = register.into();
+        if !self.as_slice().contains(&register) {
+            self.registers[self.end] = register;
+            self.end += 1;
+        }
+    }
+
+    /// Pushes a CSR, where `fcsr` is split into `frm` and `fflags`.
+    fn push_csr(&mut self, csr: Csr) {
+        if csr == Csr::FCSR {
+            self.push(Csr::FRM);
+            self.push(Csr::FFLAGS);
+        } else {
+            self.push(csr);
+        }
+    }
+
+    /// The remaining registers.
+    pub fn as_slice(&self) -> &[Register] {
+        &self.registers[self.start..self.end]
+    }
+
+    /// Whether `register` is one of the remaining registers.
+    pub fn contains(&self, register: impl Into<Register>) -> bool {
+        self.as_slice().contains(&register.into())
+    }
+}
+
+impl Iterator for Registers {
+    type Item = Register;
+
+    fn next(&mut self) -> Option<Register> {
+        let register = self.as_slice().first().copied();
+        self.start += usize::from(register.is_some());
+        register
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.end - self.start, Some(self.end - self.start))
+    }
+}
+
+impl ExactSizeIterator for Registers {}
+
+impl FusedIterator for Registers {}
+
+impl Inst {
+    /// The registers that this instruction reads.
+    ///
+    /// Besides the register operands, this includes implicit operands:
+    /// - CSR instructions read the CSR, except for `csrrw` and `csrrwi` with `rd` = `x0`
+    /// - Floating-point instructions with [`RoundingMode::Dynamic`] read `frm`
+    /// - `mret` and `sret` read `mepc` and `mstatus`, or `sepc` and `sstatus`
+    /// - Vector instructions read `vl` and `vtype`, `v0` if they are masked, the destination
+    ///   if they accumulate into it, and `frm` and `vxrm` if they round
+    /// - `amocas` reads the destination, which holds the expected value, and `amocas.q` also
+    ///   reads the odd registers of its register pairs
+    ///
+    /// `fcsr` is reported as `frm` and `fflags`, which it consists of.
+    /// The register pairs of `amocas.d` on RV32 are not reported, because they depend on XLEN.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rv_asm::{Csr, FReg, Inst, Register};
+    ///
+    /// let inst: Inst = "fadd.s fa0, fa1, fa2, dyn".parse().unwrap();
+    /// assert_eq!(
+    ///     inst.uses().as_slice(),
+    ///     [Register::Float(FReg::FA1), Register::Float(FReg::FA2), Register::Csr(Csr::FRM)]
+    /// );
+    /// assert_eq!(
+    ///     inst.defs().as_slice(),
+    ///     [Register::Float(FReg::FA0), Register::Csr(Csr::FFLAGS)]
+    /// );
+    /// ```
+    pub fn uses(&self) -> Registers {
+        self.operands().0
+    }
+
+    /// The registers that this instruction writes, except for `x0`.
+    ///
+    /// Besides the register operands, this includes implicit operands:
+    /// - CSR instructions write the CSR, except for `csrrs`, `csrrc`, `csrrsi` and `csrrci`
+    ///   without bits to set or clear
+    /// - Floating-point instructions that can raise exceptions write `fflags`
+    /// - `mret` and `sret` write `mstatus` or `sstatus`
+    /// - `vsetvl`, `vsetvli` and `vsetivli` write `vl` and `vtype`, and vector instructions
+    ///   write `fflags` and `vxsat` like their scalar counterparts
+    /// - `amocas.q` writes the odd register of its destination pair
+    ///
+    /// `fcsr` is reported as `frm` and `fflags`, which it consists of.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rv_asm::{Csr, Inst, Reg, Register};
+    ///
+    /// let inst: Inst = "csrrw zero, mscratch, a0".parse().unwrap();
+    /// assert_eq!(inst.uses().as_slice(), [Register::Int(Reg::A0)]);
+    /// assert_eq!(inst.defs().as_slice(), [Register::Csr(Csr::MSCRATCH)]);
+    /// ```
+    pub fn defs(&self) -> Registers {
+        self.operands().1
+    }
+
+    /// The registers that this instruction reads and writes.
+    fn operands(&self) -> (Registers, Registers) {
+        let mut uses = Registers::new();
+        let mut defs = Registers::new();
+
+        let accumulates = match *self {
+            Inst::Opmvv { op, .. } | Inst::Opmvx { op, .. } => op.kind() == Kind::MulAdd,
+            Inst::Opfvv { op, .. } | Inst::Opfvf { op, .. } => op.kind() == Kind::MulAdd,
+            Inst::AmocasB { .. }
+            | Inst::AmocasH { .. }
+            | Inst::AmocasW { .. }
+            | Inst::AmocasD { .. }
+            | Inst::AmocasQ { .. } => true,
+            _ => false,
+        };
+        self.for_each_register(|field, file, reg| {
+            let register = match file {
+                RegisterFile::Int => Register::Int(Reg(reg)),
+                RegisterFile::Float => Register::Float(FReg(reg)),
+                RegisterFile::Vector => Register::Vector(VReg(reg)),
+            };
+            if field != "dest" || accumulates {
+                uses.push(register);
+            }
+            if field == "dest" && register != Register::Int(Reg::ZERO) {
+                defs.push(register);
+            }
+        });
+
+        match *self {
+            Inst::Csrrw { csr, dest, .. } | Inst::Csrrwi { csr, dest, .. } => {
+                if dest != Reg::ZERO {
+                    uses.push_csr(csr);
+                }
+                defs.push_csr(csr);
+            }
+            Inst::Csrrs { csr, src, .. } | Inst::Csrrc { csr, src, .. } => {
+                uses.push_csr(csr);
+                if src != Reg::ZERO {
+                    defs.push_csr(csr);
+                }
+            }
+            Inst::Csrrsi { csr, uimm, .. } | Inst::Csrrci { csr, uimm, .. } => {
+                uses.push_csr(csr);
+                if uimm.as_u32() != 0 {
+                    defs.push_csr(csr);
+                }
+            }
+            Inst::Mret => {
+                uses.push(Csr::MEPC);
+                uses.push(Csr::MSTATUS);
+                defs.push(Csr::MSTATUS);
+            }
+            Inst::Sret => {
+                uses.push(Csr::SEPC);
+                uses.push(Csr::SSTATUS);
+                defs.push(Csr::SSTATUS);
+            }
+            // The pair of `x0` is always zero
+            Inst::AmocasQ { dest, src, .. } => {
+                if dest != Reg::ZERO {
+                    uses.push(Reg(dest.0 + 1));
+                    defs.push(Reg(dest.0 + 1));
+                }
+                if src != Reg::ZERO {
+                    uses.push(Reg(src.0 + 1));
+                }
+            }
+            _ => {}
+        }
+
+        if self.rounding_mode() == Some(RoundingMode::Dynamic) {
+            uses.push(Csr::FRM);
+        }
+        if self.raises_fp_exceptions() {
+            defs.push(Csr::FFLAGS);
+        }
+
+        if self.extension() == Extension::V {
+            self.vector_operands(&mut uses, &mut defs);
+        }
+
+        (uses, defs)
+    }
+
+    /// Adds the implicit operands of vector instructions.
+    fn vector_operands(&self, uses: &mut Registers, defs: &mut Registers) {
+        match *self {
+            Inst::Vsetvli { dest, avl, .. } | Inst::Vsetvl { dest, avl, .. } => {
+                // `vsetvli zero, zero, ...` keeps the current vector length
+                if dest == Reg::ZERO && avl == Reg::ZERO {
+                    uses.push(Csr::VL);
+                }
+                defs.push(Csr::VL);
+                defs.push(Csr::VTYPE);
+                return;
+            }
+            Inst::Vsetivli { .. } => {
+                defs.push(Csr::VL);
+                defs.push(Csr::VTYPE);
+                return;
+            }
+            // Whole register loads, stores and moves ignore `vl` and `vtype`
+            Inst::Vlr { .. } | Inst::Vsr { .. } | Inst::VmvNrR { .. } => return,
+            _ => {}
+        }
+
+        let masked = match *self {
+            Inst::Vle { masked, .. }
+            | Inst::Vleff { masked, .. }
+            | Inst::Vlse { masked, .. }
+            | Inst::Vluxei { masked, .. }
+            | Inst::Vloxei { masked, .. }
+            | Inst::Vse { masked, .. }
+            | Inst::Vsse { masked, .. }
+            | Inst::Vsuxei { masked, .. }
+            | Inst::Vsoxei { masked, .. }
+            | Inst::Opivv { masked, .. }
+            | Inst::Opivx { masked, .. }
+            | Inst::Opivi { masked, .. }
+            | Inst::Opmvv { masked, .. }
+            | Inst::Opmvx { masked, .. }
+            | Inst::Opfvv { masked, .. }
+            | Inst::Opfvf { masked, .. }
+            | Inst::VcpopM { masked, .. }
+            | Inst::VfirstM { masked, .. }
+            | Inst::Vzext { masked, .. }
+            | Inst::Vsext { masked, .. }
+            | Inst::VmsbfM { masked, .. }
+            | Inst::VmsofM { masked, .. }
+            | Inst::VmsifM { masked, .. }
+            | Inst::ViotaM { masked, .. }
+            | Inst::VidV { masked, .. }
+            | Inst::Vfcvt { masked, .. }
+            | Inst::VfUnary { masked, .. } => masked,
+            _ => false,
+        };
+        if masked {
+            uses.push(VReg(0));
+        }
+        uses.push(Csr::VL);
+        uses.push(Csr::VTYPE);
+
+        // (reads `frm`, writes `fflags`, reads `vxrm`, writes `vxsat`)
+        let (frm, fflags, vxrm, vxsat) = match *self {
+            Inst::Opivv { op, .. } | Inst::Opivx { op, .. } | Inst::Opivi { op, .. } => {
+                use OpiFunct6::*;
+                let rounds = matches!(op, Vsmul | Vssrl | Vssra | Vnclipu | Vnclip);
+                let saturates =
+                    matches!(op, Vsaddu | Vsadd | Vssubu | Vssub | Vsmul | Vnclipu | Vnclip);
+                (false, false, rounds, saturates)
+            }
+            Inst::Opmvv { op, .. } | Inst::Opmvx { op, .. } => {
+                use OpmFunct6::*;
+                (false, false, matches!(op, Vaaddu | Vaadd | Vasubu | Vasub), false)
+            }
+            Inst::Opfvv { op, .. } | Inst::Opfvf { op, .. } => {
+                use OpfFunct6::*;
+                let exact = matches!(
+                    op,
+                    Vfsgnj | Vfsgnjn | Vfsgnjx | Vfslide1up | Vfslide1down | Vfmerge
+                );
+                let rounds = !exact
+                    && !matches!(
+                        op,
+                        Vfmin | Vfmax | Vfredmin | Vfredmax | Vmfeq | Vmfle | Vmflt | Vmfne
+                            | Vmfgt | Vmfge
+                    );
+                (rounds, !exact, false, false)
+            }
+            Inst::Vfcvt { op, .. } => {
+                use VfcvtOp::*;
+                let exact = matches!(
+                    op,
+                    RtzXuF | RtzXF | WidenFF | WidenRtzXuF | WidenRtzXF | NarrowRtzXuF
+                        | NarrowRtzXF | NarrowRodFF
+                );
+                (!exact, true, false, false)
+            }
+            Inst::VfUnary { op, .. } => match op {
+                VfUnaryOp::Sqrt | VfUnaryOp::Rec7 => (true, true, false, false),
+                VfUnaryOp::Rsqrt7 => (false, true, false, false),
+                VfUnaryOp::Class => (false, false, false, false),
+            },
+            _ => (false, false, false, false),
+        };
+        if frm {
+            uses.push(Csr::FRM);
+        }
+        if vxrm {
+            uses.push(Csr::VXRM);
+        }
+        if fflags {
+            defs.push(Csr::FFLAGS);
+        }
+        if vxsat {
+            defs.push(Csr::VXSAT);
+        }
+    }
+
+    /// Whether this is a scalar floating-point instruction that can raise exceptions.
+    fn raises_fp_exceptions(&self) -> bool {
+        let fp = matches!(
+            self.extension(),
+            Extension::F
+                | Extension::D
+                | Extension::Q
+                | Extension::Zfh
+                | Extension::Zfhmin
+                | Extension::Zfa
+        );
+        fp && !matches!(
+            self,
+            Inst::Flw { .. }
+                | Inst::Fld { .. }
+                | Inst::Flh { .. }
+                | Inst::Flq { .. }
+                | Inst::Fsw { .. }
+                | Inst::Fsd { .. }
+                | Inst::Fsh { .. }
+                | Inst::Fsq { .. }
+                | Inst::FsgnjS { .. }
+                | Inst::FsgnjnS { .. }
+                | Inst::FsgnjxS { .. }
+                | Inst::FsgnjD { .. }
+                | Inst::FsgnjnD { .. }
+                | Inst::FsgnjxD { .. }
+                | Inst::FsgnjH { .. }
+                | Inst::FsgnjnH { .. }
+                | Inst::FsgnjxH { .. }
+                | Inst::FsgnjQ { .. }
+                | Inst::FsgnjnQ { .. }
+                | Inst::FsgnjxQ { .. }
+                | Inst::FmvXW { .. }
+                | Inst::FmvWX { .. }
+                | Inst::FmvXD { .. }
+                | Inst::FmvDX { .. }
+                | Inst::FmvXH { .. }
+                | Inst::FmvHX { .. }
+                | Inst::FclassS { .. }
+                | Inst::FclassD { .. }
+                | Inst::FclassH { .. }
+                | Inst::FclassQ { .. }
+                | Inst::FliS { .. }
+                | Inst::FliD { .. }
+                | Inst::FliH { .. }
+                | Inst::FmvhXD { .. }
+                | Inst::FmvpDX { .. }
+        )
+    }
+
+    /// The rounding mode of a scalar floating-point instruction.
+    fn rounding_mode(&self) -> Option<RoundingMode> {
+        match *self {
+            Inst::FmaddS { rm, .. }
+            | Inst::FmsubS { rm, .. }
+            | Inst::FnmsubS { rm, .. }
+            | Inst::FnmaddS { rm, .. }
+            | Inst::FaddS { rm, .. }
+            | Inst::FsubS { rm, .. }
+            | Inst::FmulS { rm, .. }
+            | Inst::FdivS { rm, .. }
+            | Inst::FsqrtS { rm, .. }
+            | Inst::FcvtWS { rm, .. }
+            | Inst::FcvtWuS { rm, .. }
+            | Inst::FcvtSW { rm, .. }
+            | Inst::FcvtSWu { rm, .. }
+            | Inst::FmaddD { rm, .. }
+            | Inst::FmsubD { rm, .. }
+            | Inst::FnmsubD { rm, .. }
+            | Inst::FnmaddD { rm, .. }
+            | Inst::FaddD { rm, .. }
+            | Inst::FsubD { rm, .. }
+            | Inst::FmulD { rm, .. }
+            | Inst::FdivD { rm, .. }
+            | Inst::FsqrtD { rm, .. }
+            | Inst::FcvtSD { rm, .. }
+            | Inst::FcvtDS { rm, .. }
+            | Inst::FcvtWD { rm, .. }
+            | Inst::FcvtWuD { rm, .. }
+            | Inst::FcvtDW { rm, .. }
+            | Inst::FcvtDWu { rm, .. }
+            | Inst::FcvtLS { rm, .. }
+            | Inst::FcvtLuS { rm, .. }
+            | Inst::FcvtSL { rm, .. }
+            | Inst::FcvtSLu { rm, .. }
+            | Inst::FcvtLD { rm, .. }
+            | Inst::FcvtLuD { rm, .. }
+            | Inst::FcvtDL { rm, .. }
+            | Inst::FcvtDLu { rm, .. }
+            | Inst::FmaddH { rm, .. }
+            | Inst::FmsubH { rm, .. }
+            | Inst::FnmsubH { rm, .. }
+            | Inst::FnmaddH { rm, .. }
+            | Inst::FaddH { rm, .. }
+            | Inst::FsubH { rm, .. }
+            | Inst::FmulH { rm, .. }
+            | Inst::FdivH { rm, .. }
+            | Inst::FsqrtH { rm, .. }
+            | Inst::FcvtSH { rm, .. }
+            | Inst::FcvtHS { rm, .. }
+            | Inst::FcvtDH { rm, .. }
+            | Inst::FcvtHD { rm, .. }
+            | Inst::FcvtWH { rm, .. }
+            | Inst::FcvtWuH { rm, .. }
+            | Inst::FcvtHW { rm, .. }
+            | Inst::FcvtHWu { rm, .. }
+            | Inst::FcvtLH { rm, .. }
+            | Inst::FcvtLuH { rm, .. }
+            | Inst::FcvtHL { rm, .. }
+            | Inst::FcvtHLu { rm, .. }
+            | Inst::FmaddQ { rm, .. }
+            | Inst::FmsubQ { rm, .. }
+            | Inst::FnmsubQ { rm, .. }
+            | Inst::FnmaddQ { rm, .. }
+            | Inst::FaddQ { rm, .. }
+            | Inst::FsubQ { rm, .. }
+            | Inst::FmulQ { rm, .. }
+            | Inst::FdivQ { rm, .. }
+            | Inst::FsqrtQ { rm, .. }
+            | Inst::FcvtSQ { rm, .. }
+            | Inst::FcvtQS { rm, .. }
+            | Inst::FcvtDQ { rm, .. }
+            | Inst::FcvtQD { rm, .. }
+            | Inst::FcvtHQ { rm, .. }
+            | Inst::FcvtQH { rm, .. }
+            | Inst::FcvtWQ { rm, .. }
+            | Inst::FcvtWuQ { rm, .. }
+            | Inst::FcvtQW { rm, .. }
+            | Inst::FcvtQWu { rm, .. }
+            | Inst::FcvtLQ { rm, .. }
+            | Inst::FcvtLuQ { rm, .. }
+            | Inst::FcvtQL { rm, .. }
+            | Inst::FcvtQLu { rm, .. }
+            | Inst::FroundS { rm, .. }
+            | Inst::FroundnxS { rm, .. }
+            | Inst::FroundD { rm, .. }
+            | Inst::FroundnxD { rm, .. }
+            | Inst::FroundH { rm, .. }
+            | Inst::FroundnxH { rm, .. } => Some(rm),
+            _ => None,
+        }
+    }
+}
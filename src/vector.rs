@@ -0,0 +1,685 @@
+//! Types for the V extension (RVV 1.0).
+
+use core::fmt::{self, Display};
+
+use crate::format::{Operand, Printer};
+
+/// A decoded RISC-V vector register.
+///
+/// RISC-V Specification Quote (V Extension):
+/// "The vector extension adds 32 architectural vector registers, v0-v31 to the base scalar
+/// RISC-V ISA."
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VReg(pub u8);
+
+impl VReg {
+    /// Vector register `v0`, which holds the mask of masked instructions
+    pub const V0: VReg = VReg(0);
+}
+
+impl Display for VReg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+/// A vector element width.
+///
+/// This is the selected element width (SEW) in [`Vtype`]
+/// and the effective element width (EEW) of vector loads and stores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sew {
+    /// 8 bit
+    E8,
+    /// 16 bit
+    E16,
+    /// 32 bit
+    E32,
+    /// 64 bit
+    E64,
+}
+
+impl Sew {
+    /// The width in bits.
+    pub fn bits(self) -> u32 {
+        match self {
+            Sew::E8 => 8,
+            Sew::E16 => 16,
+            Sew::E32 => 32,
+            Sew::E64 => 64,
+        }
+    }
+
+    /// The element width from the `width` field of vector loads and stores.
+    pub(crate) fn from_width(width: u32) -> Option<Self> {
+        match width {
+            0b000 => Some(Sew::E8),
+            0b101 => Some(Sew::E16),
+            0b110 => Some(Sew::E32),
+            0b111 => Some(Sew::E64),
+            _ => None,
+        }
+    }
+
+    /// The `width` field of vector loads and stores.
+    pub(crate) fn width(self) -> u32 {
+        match self {
+            Sew::E8 => 0b000,
+            Sew::E16 => 0b101,
+            Sew::E32 => 0b110,
+            Sew::E64 => 0b111,
+        }
+    }
+}
+
+impl Display for Sew {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "e{}", self.bits())
+    }
+}
+
+/// The vector register group multiplier (LMUL) in [`Vtype`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lmul {
+    /// 1/8
+    Mf8,
+    /// 1/4
+    Mf4,
+    /// 1/2
+    Mf2,
+    /// 1
+    M1,
+    /// 2
+    M2,
+    /// 4
+    M4,
+    /// 8
+    M8,
+}
+
+impl Display for Lmul {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Lmul::Mf8 => write!(f, "mf8"),
+            Lmul::Mf4 => write!(f, "mf4"),
+            Lmul::Mf2 => write!(f, "mf2"),
+            Lmul::M1 => write!(f, "m1"),
+            Lmul::M2 => write!(f, "m2"),
+            Lmul::M4 => write!(f, "m4"),
+            Lmul::M8 => write!(f, "m8"),
+        }
+    }
+}
+
+/// The vector type, as set by `vsetvli` and `vsetivli`.
+///
+/// RISC-V Specification Quote:
+/// "The XLEN-wide vector type CSR, vtype provides the default type used to interpret the
+/// contents of the vector register file, and can only be updated by vset{i}vl{i}
+/// instructions."
+///
+/// This holds the raw immediate of the instruction, so reserved values, which set `vill`
+/// when executed, are kept. They are displayed as a number like LLVM and GNU `objdump` do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Vtype(pub(crate) u16);
+
+impl Vtype {
+    /// Create a new [`Vtype`] from its fields.
+    pub fn new(sew: Sew, lmul: Lmul, tail_agnostic: bool, mask_agnostic: bool) -> Self {
+        let lmul = match lmul {
+            Lmul::M1 => 0b000,
+            Lmul::M2 => 0b001,
+            Lmul::M4 => 0b010,
+            Lmul::M8 => 0b011,
+            Lmul::Mf8 => 0b101,
+            Lmul::Mf4 => 0b110,
+            Lmul::Mf2 => 0b111,
+        };
+        let sew = match sew {
+            Sew::E8 => 0b000,
+            Sew::E16 => 0b001,
+            Sew::E32 => 0b010,
+            Sew::E64 => 0b011,
+        };
+        Self(lmul | (sew << 3) | ((tail_agnostic as u16) << 6) | ((mask_agnostic as u16) << 7))
+    }
+
+    /// Create a new [`Vtype`] from the immediate of `vsetvli` or `vsetivli`.
+    /// Returns `None` if it does not fit into the 11 bits of `vsetvli`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rv_asm::{Lmul, Sew, Vtype};
+    ///
+    /// let vtype = Vtype::from_bits(0xd0).unwrap();
+    /// assert_eq!(vtype, Vtype::new(Sew::E32, Lmul::M1, true, true));
+    /// assert_eq!(vtype.to_string(), "e32, m1, ta, ma");
+    ///
+    /// let reserved = Vtype::from_bits(0x100).unwrap();
+    /// assert!(reserved.is_reserved());
+    /// assert_eq!(reserved.to_string(), "256");
+    /// assert_eq!(Vtype::from_bits(0x800), None);
+    /// ```
+    pub fn from_bits(bits: u32) -> Option<Self> {
+        (bits < 1 << 11).then_some(Self(bits as u16))
+    }
+
+    /// Convert to the immediate of `vsetvli` or `vsetivli`.
+    pub fn bits(self) -> u32 {
+        self.0.into()
+    }
+
+    /// The selected element width (SEW), or `None` if its encoding is reserved.
+    pub fn sew(self) -> Option<Sew> {
+        match (self.0 >> 3) & 0b111 {
+            0b000 => Some(Sew::E8),
+            0b001 => Some(Sew::E16),
+            0b010 => Some(Sew::E32),
+            0b011 => Some(Sew::E64),
+            _ => None,
+        }
+    }
+
+    /// The register group multiplier (LMUL), or `None` if its encoding is reserved.
+    pub fn lmul(self) -> Option<Lmul> {
+        match self.0 & 0b111 {
+            0b000 => Some(Lmul::M1),
+            0b001 => Some(Lmul::M2),
+            0b010 => Some(Lmul::M4),
+            0b011 => Some(Lmul::M8),
+            0b101 => Some(Lmul::Mf8),
+            0b110 => Some(Lmul::Mf4),
+            0b111 => Some(Lmul::Mf2),
+            _ => None,
+        }
+    }
+
+    /// Tail agnostic (`ta`) or tail undisturbed (`tu`)
+    pub fn tail_agnostic(self) -> bool {
+        (self.0 >> 6) & 1 == 1
+    }
+
+    /// Mask agnostic (`ma`) or mask undisturbed (`mu`)
+    pub fn mask_agnostic(self) -> bool {
+        (self.0 >> 7) & 1 == 1
+    }
+
+    /// Whether reserved bits are set or the SEW or LMUL encoding is reserved.
+    pub fn is_reserved(self) -> bool {
+        self.fields().is_none()
+    }
+
+    /// The SEW and LMUL if the vtype is not reserved.
+    fn fields(self) -> Option<(Sew, Lmul)> {
+        if self.0 >> 8 != 0 {
+            return None;
+        }
+        Some((self.sew()?, self.lmul()?))
+    }
+}
+
+impl Display for Vtype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((sew, lmul)) = self.fields() else {
+            return write!(f, "{}", self.0);
+        };
+        let ta = if self.tail_agnostic() { "ta" } else { "tu" };
+        let ma = if self.mask_agnostic() { "ma" } else { "mu" };
+        write!(f, "{sew}, {lmul}, {ta}, {ma}")
+    }
+}
+
+impl Operand for Vtype {
+    fn print(&self, p: &mut Printer<'_, '_>) -> fmt::Result {
+        let Some((sew, lmul)) = self.fields() else {
+            return p.text(self.0);
+        };
+        p.text(sew)?;
+        p.separator()?;
+        p.text(lmul)?;
+        p.separator()?;
+        p.text(if self.tail_agnostic() { "ta" } else { "tu" })?;
+        p.separator()?;
+        p.text(if self.mask_agnostic() { "ma" } else { "mu" })
+    }
+}
+
+/// The operand form of a vector arithmetic instruction, named after its `src1` operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Form {
+    /// Vector-vector (`.vv`)
+    V,
+    /// Vector-scalar (`.vx`)
+    X,
+    /// Vector-immediate (`.vi`)
+    I,
+    /// Vector-scalar floating-point (`.vf`)
+    F,
+}
+
+/// How the operands and mnemonic suffix of a vector arithmetic instruction are formed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    /// `vd, vs2, src1` with a `.v*` suffix
+    Arith,
+    /// `vd, src1, vs2` with a `.v*` suffix
+    MulAdd,
+    /// `vd, vs2, src1` with a `.w*` suffix
+    Narrow,
+    /// `vd, vs2, src1` with a `v`/`x`/`f` suffix as the mnemonic already ends in `.w`
+    Wide,
+    /// `vd, vs2, vs1` with a `.vs` suffix
+    Reduce,
+    /// `vd, vs2, vs1` with a `.mm` suffix, never masked
+    Mask,
+    /// `vd, vs2, vs1` with a `.vm` suffix, never masked
+    Compress,
+    /// `vd, vs2, src1, v0` with a `.v*m` suffix, always masked
+    Carry,
+    /// Like [`Kind::Carry`] if masked, otherwise [`Kind::Arith`]
+    CarryOut,
+    /// Like [`Kind::Carry`] if masked, otherwise `vmv.v*`/`vfmv.v.f` with only `vd, src1`
+    Merge,
+}
+
+impl Kind {
+    /// The suffix following the mnemonic.
+    pub(crate) fn suffix(self, form: Form, masked: bool) -> &'static str {
+        let carry = match self {
+            Kind::Carry => true,
+            Kind::CarryOut | Kind::Merge => masked,
+            _ => false,
+        };
+        match (self, form, carry) {
+            (Kind::Reduce, ..) => ".vs",
+            (Kind::Mask, ..) => ".mm",
+            (Kind::Compress, ..) => ".vm",
+            (Kind::Wide, Form::V, _) => "v",
+            (Kind::Wide, Form::X, _) => "x",
+            (Kind::Wide, _, _) => "f",
+            (Kind::Narrow, Form::V, _) => ".wv",
+            (Kind::Narrow, Form::X, _) => ".wx",
+            (Kind::Narrow, _, _) => ".wi",
+            (_, Form::V, false) => ".vv",
+            (_, Form::X, false) => ".vx",
+            (_, Form::I, false) => ".vi",
+            (_, Form::F, false) => ".vf",
+            (_, Form::V, true) => ".vvm",
+            (_, Form::X, true) => ".vxm",
+            (_, Form::I, true) => ".vim",
+            (_, Form::F, true) => ".vfm",
+        }
+    }
+}
+
+/// Writes a vector arithmetic instruction.
+#[expect(clippy::too_many_arguments)]
+pub(crate) fn fmt_arith(
+    p: &mut Printer<'_, '_>,
+    mnemonic: &str,
+    kind: Kind,
+    form: Form,
+    dest: VReg,
+    src2: VReg,
+    src1: &dyn Operand,
+    masked: bool,
+) -> fmt::Result {
+    if kind == Kind::Merge && !masked {
+        let mv = if form == Form::F { "vfmv" } else { "vmv" };
+        let form = match form {
+            Form::V => "v",
+            Form::X => "x",
+            Form::I => "i",
+            Form::F => "f",
+        };
+        return p.inst(format_args!("{mv}.v.{form}"), &[&dest, src1]);
+    }
+    let suffix = kind.suffix(form, masked);
+    let [first, second]: [&dyn Operand; 2] =
+        if kind == Kind::MulAdd { [src1, &src2] } else { [&src2, src1] };
+    match kind {
+        Kind::Carry | Kind::CarryOut | Kind::Merge if masked => {
+            p.inst(format_args!("{mnemonic}{suffix}"), &[&dest, first, second, &"v0"])
+        }
+        _ => p.inst_masked(format_args!("{mnemonic}{suffix}"), &[&dest, first, second], masked),
+    }
+}
+
+macro_rules! vector_ops {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($variant:ident = $funct6:literal, $mnemonic:literal, $kind:ident, $($form:ident)|+;)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $(
+                #[doc = concat!("`", $mnemonic, "`")]
+                $variant,
+            )*
+        }
+
+        impl $name {
+            pub(crate) const ALL: &[Self] = &[$(Self::$variant),*];
+
+            /// The `funct6` field of the instruction.
+            pub fn funct6(self) -> u32 {
+                match self {
+                    $(Self::$variant => $funct6,)*
+                }
+            }
+
+            /// The mnemonic without the operand suffix, for example `vadd` for `vadd.vv`.
+            pub fn mnemonic(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $mnemonic,)*
+                }
+            }
+
+            pub(crate) fn kind(self) -> Kind {
+                match self {
+                    $(Self::$variant => Kind::$kind,)*
+                }
+            }
+
+            pub(crate) fn has_form(self, form: Form) -> bool {
+                match self {
+                    $(Self::$variant => matches!(form, $(Form::$form)|+),)*
+                }
+            }
+
+            pub(crate) fn decode(funct6: u32, form: Form) -> Option<Self> {
+                Self::ALL
+                    .iter()
+                    .copied()
+                    .find(|op| op.funct6() == funct6 && op.has_form(form))
+            }
+
+            /// Looks up a mnemonic including its suffix, like `vadd.vv`.
+            /// Also returns whether the suffix is that of the masked form (`.vvm`).
+            pub(crate) fn parse(mnemonic: &str) -> Option<(Self, Form, bool)> {
+                for &op in Self::ALL {
+                    let Some(suffix) = mnemonic.strip_prefix(op.mnemonic()) else {
+                        continue;
+                    };
+                    for form in [Form::V, Form::X, Form::I, Form::F] {
+                        for masked in [false, true] {
+                            if !op.has_form(form)
+                                || (!masked && matches!(op.kind(), Kind::Carry | Kind::Merge))
+                            {
+                                continue;
+                            }
+                            if op.kind().suffix(form, masked) == suffix {
+                                return Some((op, form, masked));
+                            }
+                        }
+                    }
+                }
+                None
+            }
+        }
+    };
+}
+
+vector_ops! {
+    /// An operation of the OPIVV, OPIVX and OPIVI funct3 categories (integer instructions).
+    pub enum OpiFunct6 {
+        Vadd = 0b000000, "vadd", Arith, V | X | I;
+        Vsub = 0b000010, "vsub", Arith, V | X;
+        Vrsub = 0b000011, "vrsub", Arith, X | I;
+        Vminu = 0b000100, "vminu", Arith, V | X;
+        Vmin = 0b000101, "vmin", Arith, V | X;
+        Vmaxu = 0b000110, "vmaxu", Arith, V | X;
+        Vmax = 0b000111, "vmax", Arith, V | X;
+        Vand = 0b001001, "vand", Arith, V | X | I;
+        Vor = 0b001010, "vor", Arith, V | X | I;
+        Vxor = 0b001011, "vxor", Arith, V | X | I;
+        Vrgather = 0b001100, "vrgather", Arith, V | X | I;
+        Vrgatherei16 = 0b001110, "vrgatherei16", Arith, V;
+        Vslideup = 0b001110, "vslideup", Arith, X | I;
+        Vslidedown = 0b001111, "vslidedown", Arith, X | I;
+        Vadc = 0b010000, "vadc", Carry, V | X | I;
+        Vmadc = 0b010001, "vmadc", CarryOut, V | X | I;
+        Vsbc = 0b010010, "vsbc", Carry, V | X;
+        Vmsbc = 0b010011, "vmsbc", CarryOut, V | X;
+        Vmerge = 0b010111, "vmerge", Merge, V | X | I;
+        Vmseq = 0b011000, "vmseq", Arith, V | X | I;
+        Vmsne = 0b011001, "vmsne", Arith, V | X | I;
+        Vmsltu = 0b011010, "vmsltu", Arith, V | X;
+        Vmslt = 0b011011, "vmslt", Arith, V | X;
+        Vmsleu = 0b011100, "vmsleu", Arith, V | X | I;
+        Vmsle = 0b011101, "vmsle", Arith, V | X | I;
+        Vmsgtu = 0b011110, "vmsgtu", Arith, X | I;
+        Vmsgt = 0b011111, "vmsgt", Arith, X | I;
+        Vsaddu = 0b100000, "vsaddu", Arith, V | X | I;
+        Vsadd = 0b100001, "vsadd", Arith, V | X | I;
+        Vssubu = 0b100010, "vssubu", Arith, V | X;
+        Vssub = 0b100011, "vssub", Arith, V | X;
+        Vsll = 0b100101, "vsll", Arith, V | X | I;
+        Vsmul = 0b100111, "vsmul", Arith, V | X;
+        Vsrl = 0b101000, "vsrl", Arith, V | X | I;
+        Vsra = 0b101001, "vsra", Arith, V | X | I;
+        Vssrl = 0b101010, "vssrl", Arith, V | X | I;
+        Vssra = 0b101011, "vssra", Arith, V | X | I;
+        Vnsrl = 0b101100, "vnsrl", Narrow, V | X | I;
+        Vnsra = 0b101101, "vnsra", Narrow, V | X | I;
+        Vnclipu = 0b101110, "vnclipu", Narrow, V | X | I;
+        Vnclip = 0b101111, "vnclip", Narrow, V | X | I;
+        Vwredsumu = 0b110000, "vwredsumu", Reduce, V;
+        Vwredsum = 0b110001, "vwredsum", Reduce, V;
+    }
+}
+
+impl OpiFunct6 {
+    /// Whether the immediate of the OPIVI form is unsigned.
+    pub fn has_unsigned_imm(self) -> bool {
+        matches!(
+            self,
+            OpiFunct6::Vrgather
+                | OpiFunct6::Vslideup
+                | OpiFunct6::Vslidedown
+                | OpiFunct6::Vsll
+                | OpiFunct6::Vsrl
+                | OpiFunct6::Vsra
+                | OpiFunct6::Vssrl
+                | OpiFunct6::Vssra
+                | OpiFunct6::Vnsrl
+                | OpiFunct6::Vnsra
+                | OpiFunct6::Vnclipu
+                | OpiFunct6::Vnclip
+        )
+    }
+}
+
+vector_ops! {
+    /// An operation of the OPMVV and OPMVX funct3 categories
+    /// (integer multiply, reduction and mask instructions).
+    ///
+    /// The unary operations that are selected by the `vs1` or `vs2` field are separate [`crate::Inst`] variants.
+    pub enum OpmFunct6 {
+        Vredsum = 0b000000, "vredsum", Reduce, V;
+        Vredand = 0b000001, "vredand", Reduce, V;
+        Vredor = 0b000010, "vredor", Reduce, V;
+        Vredxor = 0b000011, "vredxor", Reduce, V;
+        Vredminu = 0b000100, "vredminu", Reduce, V;
+        Vredmin = 0b000101, "vredmin", Reduce, V;
+        Vredmaxu = 0b000110, "vredmaxu", Reduce, V;
+        Vredmax = 0b000111, "vredmax", Reduce, V;
+        Vaaddu = 0b001000, "vaaddu", Arith, V | X;
+        Vaadd = 0b001001, "vaadd", Arith, V | X;
+        Vasubu = 0b001010, "vasubu", Arith, V | X;
+        Vasub = 0b001011, "vasub", Arith, V | X;
+        Vslide1up = 0b001110, "vslide1up", Arith, X;
+        Vslide1down = 0b001111, "vslide1down", Arith, X;
+        Vcompress = 0b010111, "vcompress", Compress, V;
+        Vmandn = 0b011000, "vmandn", Mask, V;
+        Vmand = 0b011001, "vmand", Mask, V;
+        Vmor = 0b011010, "vmor", Mask, V;
+        Vmxor = 0b011011, "vmxor", Mask, V;
+        Vmorn = 0b011100, "vmorn", Mask, V;
+        Vmnand = 0b011101, "vmnand", Mask, V;
+        Vmnor = 0b011110, "vmnor", Mask, V;
+        Vmxnor = 0b011111, "vmxnor", Mask, V;
+        Vdivu = 0b100000, "vdivu", Arith, V | X;
+        Vdiv = 0b100001, "vdiv", Arith, V | X;
+        Vremu = 0b100010, "vremu", Arith, V | X;
+        Vrem = 0b100011, "vrem", Arith, V | X;
+        Vmulhu = 0b100100, "vmulhu", Arith, V | X;
+        Vmul = 0b100101, "vmul", Arith, V | X;
+        Vmulhsu = 0b100110, "vmulhsu", Arith, V | X;
+        Vmulh = 0b100111, "vmulh", Arith, V | X;
+        Vmadd = 0b101001, "vmadd", MulAdd, V | X;
+        Vnmsub = 0b101011, "vnmsub", MulAdd, V | X;
+        Vmacc = 0b101101, "vmacc", MulAdd, V | X;
+        Vnmsac = 0b101111, "vnmsac", MulAdd, V | X;
+        Vwaddu = 0b110000, "vwaddu", Arith, V | X;
+        Vwadd = 0b110001, "vwadd", Arith, V | X;
+        Vwsubu = 0b110010, "vwsubu", Arith, V | X;
+        Vwsub = 0b110011, "vwsub", Arith, V | X;
+        VwadduW = 0b110100, "vwaddu.w", Wide, V | X;
+        VwaddW = 0b110101, "vwadd.w", Wide, V | X;
+        VwsubuW = 0b110110, "vwsubu.w", Wide, V | X;
+        VwsubW = 0b110111, "vwsub.w", Wide, V | X;
+        Vwmulu = 0b111000, "vwmulu", Arith, V | X;
+        Vwmulsu = 0b111010, "vwmulsu", Arith, V | X;
+        Vwmul = 0b111011, "vwmul", Arith, V | X;
+        Vwmaccu = 0b111100, "vwmaccu", MulAdd, V | X;
+        Vwmacc = 0b111101, "vwmacc", MulAdd, V | X;
+        Vwmaccus = 0b111110, "vwmaccus", MulAdd, X;
+        Vwmaccsu = 0b111111, "vwmaccsu", MulAdd, V | X;
+    }
+}
+
+vector_ops! {
+    /// An operation of the OPFVV and OPFVF funct3 categories (floating-point instructions).
+    ///
+    /// The unary operations that are selected by the `vs1` or `vs2` field are separate [`crate::Inst`] variants.
+    pub enum OpfFunct6 {
+        Vfadd = 0b000000, "vfadd", Arith, V | F;
+        Vfredusum = 0b000001, "vfredusum", Reduce, V;
+        Vfsub = 0b000010, "vfsub", Arith, V | F;
+        Vfredosum = 0b000011, "vfredosum", Reduce, V;
+        Vfmin = 0b000100, "vfmin", Arith, V | F;
+        Vfredmin = 0b000101, "vfredmin", Reduce, V;
+        Vfmax = 0b000110, "vfmax", Arith, V | F;
+        Vfredmax = 0b000111, "vfredmax", Reduce, V;
+        Vfsgnj = 0b001000, "vfsgnj", Arith, V | F;
+        Vfsgnjn = 0b001001, "vfsgnjn", Arith, V | F;
+        Vfsgnjx = 0b001010, "vfsgnjx", Arith, V | F;
+        Vfslide1up = 0b001110, "vfslide1up", Arith, F;
+        Vfslide1down = 0b001111, "vfslide1down", Arith, F;
+        Vfmerge = 0b010111, "vfmerge", Merge, F;
+        Vmfeq = 0b011000, "vmfeq", Arith, V | F;
+        Vmfle = 0b011001, "vmfle", Arith, V | F;
+        Vmflt = 0b011011, "vmflt", Arith, V | F;
+        Vmfne = 0b011100, "vmfne", Arith, V | F;
+        Vmfgt = 0b011101, "vmfgt", Arith, F;
+        Vmfge = 0b011111, "vmfge", Arith, F;
+        Vfdiv = 0b100000, "vfdiv", Arith, V | F;
+        Vfrdiv = 0b100001, "vfrdiv", Arith, F;
+        Vfmul = 0b100100, "vfmul", Arith, V | F;
+        Vfrsub = 0b100111, "vfrsub", Arith, F;
+        Vfmadd = 0b101000, "vfmadd", MulAdd, V | F;
+        Vfnmadd = 0b101001, "vfnmadd", MulAdd, V | F;
+        Vfmsub = 0b101010, "vfmsub", MulAdd, V | F;
+        Vfnmsub = 0b101011, "vfnmsub", MulAdd, V | F;
+        Vfmacc = 0b101100, "vfmacc", MulAdd, V | F;
+        Vfnmacc = 0b101101, "vfnmacc", MulAdd, V | F;
+        Vfmsac = 0b101110, "vfmsac", MulAdd, V | F;
+        Vfnmsac = 0b101111, "vfnmsac", MulAdd, V | F;
+        Vfwadd = 0b110000, "vfwadd", Arith, V | F;
+        Vfwredusum = 0b110001, "vfwredusum", Reduce, V;
+        Vfwsub = 0b110010, "vfwsub", Arith, V | F;
+        Vfwredosum = 0b110011, "vfwredosum", Reduce, V;
+        VfwaddW = 0b110100, "vfwadd.w", Wide, V | F;
+        VfwsubW = 0b110110, "vfwsub.w", Wide, V | F;
+        Vfwmul = 0b111000, "vfwmul", Arith, V | F;
+        Vfwmacc = 0b111100, "vfwmacc", MulAdd, V | F;
+        Vfwnmacc = 0b111101, "vfwnmacc", MulAdd, V | F;
+        Vfwmsac = 0b111110, "vfwmsac", MulAdd, V | F;
+        Vfwnmsac = 0b111111, "vfwnmsac", MulAdd, V | F;
+    }
+}
+
+macro_rules! vector_unary_ops {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($variant:ident = $vs1:literal, $mnemonic:literal;)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $(
+                #[doc = concat!("`", $mnemonic, "`")]
+                $variant,
+            )*
+        }
+
+        impl $name {
+            pub(crate) const ALL: &[Self] = &[$(Self::$variant),*];
+
+            /// The `vs1` field selecting the operation.
+            pub fn vs1(self) -> u32 {
+                match self {
+                    $(Self::$variant => $vs1,)*
+                }
+            }
+
+            /// The full mnemonic of the instruction.
+            pub fn mnemonic(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $mnemonic,)*
+                }
+            }
+
+            pub(crate) fn decode(vs1: u32) -> Option<Self> {
+                Self::ALL.iter().copied().find(|op| op.vs1() == vs1)
+            }
+
+            pub(crate) fn parse(mnemonic: &str) -> Option<Self> {
+                Self::ALL.iter().copied().find(|op| op.mnemonic() == mnemonic)
+            }
+        }
+    };
+}
+
+vector_unary_ops! {
+    /// A floating-point conversion of the VFUNARY0 encoding space.
+    pub enum VfcvtOp {
+        XuF = 0b00000, "vfcvt.xu.f.v";
+        XF = 0b00001, "vfcvt.x.f.v";
+        FXu = 0b00010, "vfcvt.f.xu.v";
+        FX = 0b00011, "vfcvt.f.x.v";
+        RtzXuF = 0b00110, "vfcvt.rtz.xu.f.v";
+        RtzXF = 0b00111, "vfcvt.rtz.x.f.v";
+        WidenXuF = 0b01000, "vfwcvt.xu.f.v";
+        WidenXF = 0b01001, "vfwcvt.x.f.v";
+        WidenFXu = 0b01010, "vfwcvt.f.xu.v";
+        WidenFX = 0b01011, "vfwcvt.f.x.v";
+        WidenFF = 0b01100, "vfwcvt.f.f.v";
+        WidenRtzXuF = 0b01110, "vfwcvt.rtz.xu.f.v";
+        WidenRtzXF = 0b01111, "vfwcvt.rtz.x.f.v";
+        NarrowXuF = 0b10000, "vfncvt.xu.f.w";
+        NarrowXF = 0b10001, "vfncvt.x.f.w";
+        NarrowFXu = 0b10010, "vfncvt.f.xu.w";
+        NarrowFX = 0b10011, "vfncvt.f.x.w";
+        NarrowFF = 0b10100, "vfncvt.f.f.w";
+        NarrowRodFF = 0b10101, "vfncvt.rod.f.f.w";
+        NarrowRtzXuF = 0b10110, "vfncvt.rtz.xu.f.w";
+        NarrowRtzXF = 0b10111, "vfncvt.rtz.x.f.w";
+    }
+}
+
+vector_unary_ops! {
+    /// A floating-point operation of the VFUNARY1 encoding space.
+    pub enum VfUnaryOp {
+        Sqrt = 0b00000, "vfsqrt.v";
+        Rsqrt7 = 0b00100, "vfrsqrt7.v";
+        Rec7 = 0b00101, "vfrec7.v";
+        Class = 0b10000, "vfclass.v";
+    }
+}